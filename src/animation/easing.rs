@@ -0,0 +1,190 @@
+//! Easing curves
+//!
+//! Standard easing functions mapping normalized time (0.0 to 1.0) to progress.
+
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+/// Easing function applied to tween progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Pulls back slightly before moving forward
+    BackIn,
+    /// Overshoots the target before settling
+    BackOut,
+    BackInOut,
+    /// Spring-like oscillation at the start
+    ElasticIn,
+    /// Spring-like oscillation at the end
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    /// Bounces against the target like a dropped ball
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Evaluate the curve at `t`, clamped to 0.0..=1.0
+    ///
+    /// The output starts at 0.0 and ends at 1.0 but may leave that range
+    /// in between for back and elastic curves.
+    #[must_use]
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => t,
+            Self::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Self::SineOut => (t * PI / 2.0).sin(),
+            Self::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::BackIn => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                C3 * t * t * t - C1 * t * t
+            }
+            Self::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+            Self::BackInOut => {
+                const C2: f32 = 1.70158 * 1.525;
+                if t < 0.5 {
+                    ((2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2)) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+                }
+            }
+            Self::ElasticIn => {
+                const C4: f32 = 2.0 * PI / 3.0;
+                if t <= 0.0 || t >= 1.0 {
+                    t
+                } else {
+                    -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * C4).sin()
+                }
+            }
+            Self::ElasticOut => {
+                const C4: f32 = 2.0 * PI / 3.0;
+                if t <= 0.0 || t >= 1.0 {
+                    t
+                } else {
+                    2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0
+                }
+            }
+            Self::ElasticInOut => {
+                const C5: f32 = 2.0 * PI / 4.5;
+                if t <= 0.0 || t >= 1.0 {
+                    t
+                } else if t < 0.5 {
+                    -(2.0_f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+                } else {
+                    (2.0_f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0 + 1.0
+                }
+            }
+            Self::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Self::BounceOut => bounce_out(t),
+            Self::BounceInOut => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 19] = [
+        Easing::Linear,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    #[test]
+    fn test_endpoints() {
+        for easing in ALL {
+            assert!(easing.apply(0.0).abs() < 0.001, "{easing:?} at 0");
+            assert!((easing.apply(1.0) - 1.0).abs() < 0.001, "{easing:?} at 1");
+        }
+    }
+
+    #[test]
+    fn test_curve_shapes() {
+        assert!(Easing::QuadIn.apply(0.5) < 0.5);
+        assert!(Easing::QuadOut.apply(0.5) > 0.5);
+        assert!((Easing::CubicInOut.apply(0.5) - 0.5).abs() < 0.001);
+        // Back overshoots, in pulls below zero
+        assert!(Easing::BackIn.apply(0.2) < 0.0);
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+    }
+}
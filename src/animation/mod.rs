@@ -1,11 +1,15 @@
 //! Animation system
 //!
-//! Provides skeletal animation, animation clips, and playback control.
+//! Provides skeletal animation, animation clips, playback control, and tweening.
 
 mod clip;
+mod easing;
 mod player;
 mod skeleton;
+mod tween;
 
 pub use clip::{AnimationClip, Channel, Interpolation, Keyframe};
+pub use easing::Easing;
 pub use player::{AnimationPlayer, PlaybackState};
 pub use skeleton::{Bone, Skeleton, SkinningData};
+pub use tween::{LoopMode, Tween, TweenCompleted, TweenSequence, TweenTarget, update_tweens};
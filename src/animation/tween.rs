@@ -0,0 +1,485 @@
+//! Tweening components
+//!
+//! Animates transforms and reflected component properties over time.
+
+use glam::{Quat, Vec3};
+use hecs::Entity;
+
+use super::easing::Easing;
+use crate::ecs::{Disabled, Transform, Value, World};
+
+/// What a tween animates
+#[derive(Debug, Clone, PartialEq)]
+pub enum TweenTarget {
+    /// Transform position
+    Position { from: Vec3, to: Vec3 },
+    /// Transform rotation (spherical interpolation)
+    Rotation { from: Quat, to: Quat },
+    /// Transform scale
+    Scale { from: Vec3, to: Vec3 },
+    /// A reflected component field, e.g. a `Vec4` color
+    ///
+    /// `F32`, `Vec2`, `Vec3`, `Vec4` and `Quat` values are interpolated; other
+    /// value types switch from `from` to `to` at the end of the tween.
+    Property {
+        component: String,
+        path: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+/// How a tween repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Play once
+    #[default]
+    Once,
+    /// Restart from the beginning
+    Loop,
+    /// Alternate between forwards and backwards
+    PingPong,
+}
+
+/// Emitted when a tween or tween sequence finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TweenCompleted {
+    /// Entity the tween was attached to
+    pub entity: Entity,
+    /// User-defined tween ID
+    pub id: u64,
+}
+
+/// Tween component animating one target
+///
+/// Finished tweens are removed from their entity by [`update_tweens`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween {
+    /// Animated target
+    pub target: TweenTarget,
+    /// Duration of one cycle in seconds
+    pub duration: f32,
+    /// Easing curve
+    pub easing: Easing,
+    /// Delay before the first cycle starts
+    pub delay: f32,
+    /// Repeat behavior
+    pub loop_mode: LoopMode,
+    /// Number of cycles for `Loop`/`PingPong` (`None` repeats forever)
+    pub cycles: Option<u32>,
+    /// User-defined ID reported in [`TweenCompleted`]
+    pub id: u64,
+    /// Time since the tween started, including delay
+    elapsed: f32,
+    /// Whether the tween has finished
+    finished: bool,
+}
+
+impl Tween {
+    /// Create a tween
+    #[must_use]
+    pub fn new(target: TweenTarget, duration: f32) -> Self {
+        Self {
+            target,
+            duration,
+            easing: Easing::Linear,
+            delay: 0.0,
+            loop_mode: LoopMode::Once,
+            cycles: None,
+            id: 0,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Tween the transform position
+    #[must_use]
+    pub fn position(from: Vec3, to: Vec3, duration: f32) -> Self {
+        Self::new(TweenTarget::Position { from, to }, duration)
+    }
+
+    /// Tween the transform rotation
+    #[must_use]
+    pub fn rotation(from: Quat, to: Quat, duration: f32) -> Self {
+        Self::new(TweenTarget::Rotation { from, to }, duration)
+    }
+
+    /// Tween the transform scale
+    #[must_use]
+    pub fn scale(from: Vec3, to: Vec3, duration: f32) -> Self {
+        Self::new(TweenTarget::Scale { from, to }, duration)
+    }
+
+    /// Tween a reflected component field by path
+    #[must_use]
+    pub fn property(
+        component: impl Into<String>,
+        path: impl Into<String>,
+        from: Value,
+        to: Value,
+        duration: f32,
+    ) -> Self {
+        Self::new(
+            TweenTarget::Property {
+                component: component.into(),
+                path: path.into(),
+                from,
+                to,
+            },
+            duration,
+        )
+    }
+
+    /// Set the easing curve
+    #[must_use]
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Set the start delay in seconds
+    #[must_use]
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Repeat from the beginning (`None` repeats forever)
+    #[must_use]
+    pub fn looping(mut self, cycles: Option<u32>) -> Self {
+        self.loop_mode = LoopMode::Loop;
+        self.cycles = cycles;
+        self
+    }
+
+    /// Alternate direction each cycle (`None` repeats forever)
+    #[must_use]
+    pub fn ping_pong(mut self, cycles: Option<u32>) -> Self {
+        self.loop_mode = LoopMode::PingPong;
+        self.cycles = cycles;
+        self
+    }
+
+    /// Set the ID reported on completion
+    #[must_use]
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Check if the tween has finished
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Restart the tween from the beginning, including the delay
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+
+    /// Total cycles to play, `None` for infinite
+    fn total_cycles(&self) -> Option<u32> {
+        match self.loop_mode {
+            LoopMode::Once => Some(1),
+            LoopMode::Loop | LoopMode::PingPong => self.cycles.map(|c| c.max(1)),
+        }
+    }
+
+    /// Advance time and return the eased progress, or `None` while delayed
+    fn advance(&mut self, dt: f32) -> Option<f32> {
+        self.elapsed += dt;
+        let active = self.elapsed - self.delay;
+        if active < 0.0 {
+            return None;
+        }
+
+        if self.duration <= 0.0 {
+            self.finished = true;
+            return Some(self.easing.apply(1.0));
+        }
+
+        let mut cycle = (active / self.duration).floor() as u32;
+        let mut local = (active % self.duration) / self.duration;
+        if let Some(total) = self.total_cycles()
+            && cycle >= total
+        {
+            self.finished = true;
+            cycle = total - 1;
+            local = 1.0;
+        }
+
+        let reversed = self.loop_mode == LoopMode::PingPong && cycle % 2 == 1;
+        let t = if reversed { 1.0 - local } else { local };
+        Some(self.easing.apply(t))
+    }
+
+    /// Time left over after finishing, carried into the next tween of a sequence
+    fn overshoot(&self) -> f32 {
+        let total = self
+            .total_cycles()
+            .map_or(f32::INFINITY, |c| c as f32 * self.duration.max(0.0));
+        (self.elapsed - self.delay - total).max(0.0)
+    }
+
+    /// Compute the value to apply at eased progress `t`
+    fn sample(&self, t: f32) -> Sample {
+        match &self.target {
+            TweenTarget::Position { from, to } => Sample::Position(from.lerp(*to, t)),
+            TweenTarget::Rotation { from, to } => Sample::Rotation(from.slerp(*to, t)),
+            TweenTarget::Scale { from, to } => Sample::Scale(from.lerp(*to, t)),
+            TweenTarget::Property {
+                component,
+                path,
+                from,
+                to,
+            } => Sample::Property {
+                component: component.clone(),
+                path: path.clone(),
+                value: lerp_value(from, to, t, self.finished),
+            },
+        }
+    }
+}
+
+/// Tweens played one after another
+#[derive(Debug, Clone, PartialEq)]
+pub struct TweenSequence {
+    /// Tweens in playback order
+    pub tweens: Vec<Tween>,
+    /// User-defined ID reported when the whole sequence completes
+    pub id: u64,
+    /// Index of the current tween
+    current: usize,
+}
+
+impl TweenSequence {
+    /// Create a sequence from tweens
+    #[must_use]
+    pub fn new(tweens: Vec<Tween>) -> Self {
+        Self {
+            tweens,
+            id: 0,
+            current: 0,
+        }
+    }
+
+    /// Append a tween (builder style)
+    #[must_use]
+    pub fn then(mut self, tween: Tween) -> Self {
+        self.tweens.push(tween);
+        self
+    }
+
+    /// Set the ID reported on completion
+    #[must_use]
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Index of the currently playing tween
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Check if all tweens have finished
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.tweens.len()
+    }
+}
+
+/// A value computed by a tween, applied after iteration
+#[derive(Debug, Clone)]
+enum Sample {
+    Position(Vec3),
+    Rotation(Quat),
+    Scale(Vec3),
+    Property {
+        component: String,
+        path: String,
+        value: Value,
+    },
+}
+
+/// Interpolate between two reflected values
+fn lerp_value(from: &Value, to: &Value, t: f32, finished: bool) -> Value {
+    match (from, to) {
+        (Value::F32(a), Value::F32(b)) => Value::F32(a + (b - a) * t),
+        (Value::Vec2(a), Value::Vec2(b)) => Value::Vec2(a.lerp(*b, t)),
+        (Value::Vec3(a), Value::Vec3(b)) => Value::Vec3(a.lerp(*b, t)),
+        (Value::Vec4(a), Value::Vec4(b)) => Value::Vec4(a.lerp(*b, t)),
+        (Value::Quat(a), Value::Quat(b)) => Value::Quat(a.slerp(*b, t)),
+        _ if finished => to.clone(),
+        _ => from.clone(),
+    }
+}
+
+/// Advance all [`Tween`] and [`TweenSequence`] components and apply their values
+///
+/// Finished tweens and sequences are removed from their entities.
+/// Returns the completion events for this update.
+pub fn update_tweens(world: &mut World, dt: f32) -> Vec<TweenCompleted> {
+    let mut samples = Vec::new();
+    let mut completed = Vec::new();
+    let mut finished_tweens = Vec::new();
+    let mut finished_sequences = Vec::new();
+
+    for (entity, tween) in world.query_mut::<&mut Tween>().without::<&Disabled>() {
+        if let Some(t) = tween.advance(dt) {
+            samples.push((entity, tween.sample(t)));
+        }
+        if tween.is_finished() {
+            completed.push(TweenCompleted {
+                entity,
+                id: tween.id,
+            });
+            finished_tweens.push(entity);
+        }
+    }
+
+    for (entity, sequence) in world
+        .query_mut::<&mut TweenSequence>()
+        .without::<&Disabled>()
+    {
+        let mut dt_left = dt;
+        while let Some(tween) = sequence.tweens.get_mut(sequence.current) {
+            if let Some(t) = tween.advance(dt_left) {
+                samples.push((entity, tween.sample(t)));
+            }
+            if !tween.is_finished() {
+                break;
+            }
+
+            completed.push(TweenCompleted {
+                entity,
+                id: tween.id,
+            });
+            dt_left = tween.overshoot();
+            sequence.current += 1;
+        }
+
+        if sequence.is_finished() {
+            completed.push(TweenCompleted {
+                entity,
+                id: sequence.id,
+            });
+            finished_sequences.push(entity);
+        }
+    }
+
+    for (entity, sample) in samples {
+        apply_sample(world, entity, sample);
+    }
+    for entity in finished_tweens {
+        let _ = world.remove_one::<Tween>(entity);
+    }
+    for entity in finished_sequences {
+        let _ = world.remove_one::<TweenSequence>(entity);
+    }
+
+    completed
+}
+
+fn apply_sample(world: &mut World, entity: Entity, sample: Sample) {
+    match sample {
+        Sample::Position(position) => {
+            if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+                transform.position = position;
+            }
+        }
+        Sample::Rotation(rotation) => {
+            if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+                transform.rotation = rotation;
+            }
+        }
+        Sample::Scale(scale) => {
+            if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+                transform.scale = scale;
+            }
+        }
+        Sample::Property {
+            component,
+            path,
+            value,
+        } => {
+            if let Err(e) = world.set_field(entity, &component, &path, value) {
+                log::warn!("Tween could not set {component}.{path}: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Name;
+
+    #[test]
+    fn test_tween_delay_and_completion() {
+        let mut world = World::new();
+        let entity = world.spawn((
+            Transform::default(),
+            Tween::position(Vec3::ZERO, Vec3::X * 10.0, 1.0)
+                .with_delay(0.5)
+                .with_id(7),
+        ));
+
+        assert!(update_tweens(&mut world, 0.25).is_empty());
+        assert_eq!(world.get::<Transform>(entity).unwrap().position, Vec3::ZERO);
+
+        update_tweens(&mut world, 0.75);
+        assert!((world.get::<Transform>(entity).unwrap().position.x - 5.0).abs() < 0.001);
+
+        let events = update_tweens(&mut world, 1.0);
+        assert_eq!(events, vec![TweenCompleted { entity, id: 7 }]);
+        assert_eq!(world.get::<Transform>(entity).unwrap().position.x, 10.0);
+        assert!(world.get::<Tween>(entity).is_err());
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        let mut tween = Tween::scale(Vec3::ZERO, Vec3::ONE, 1.0).ping_pong(Some(2));
+
+        assert!((tween.advance(0.75).unwrap() - 0.75).abs() < 0.001);
+        // Second cycle runs backwards
+        assert!((tween.advance(0.5).unwrap() - 0.75).abs() < 0.001);
+        assert!(!tween.is_finished());
+        assert!(tween.advance(1.0).unwrap().abs() < 0.001);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn test_sequence_and_property() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::default(), Name::new("Door")));
+        world
+            .insert_one(
+                entity,
+                TweenSequence::new(vec![
+                    Tween::position(Vec3::ZERO, Vec3::Y, 1.0).with_id(1),
+                    Tween::property(
+                        "Transform",
+                        "scale.x",
+                        Value::F32(1.0),
+                        Value::F32(3.0),
+                        1.0,
+                    )
+                    .with_id(2),
+                ])
+                .with_id(3),
+            )
+            .unwrap();
+
+        let events = update_tweens(&mut world, 1.5);
+        assert_eq!(events.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1]);
+        assert!((world.get::<Transform>(entity).unwrap().scale.x - 2.0).abs() < 0.001);
+
+        let events = update_tweens(&mut world, 1.0);
+        assert_eq!(events.iter().map(|e| e.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(world.get::<TweenSequence>(entity).is_err());
+    }
+}
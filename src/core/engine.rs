@@ -1,19 +1,23 @@
 //! Core Engine struct and main game loop
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use winit::{
     application::ApplicationHandler,
-    dpi::PhysicalSize,
-    event::WindowEvent,
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{DeviceEvent, DeviceId, ElementState, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
-    window::{Window, WindowId},
+    window::{CursorGrabMode, Window, WindowId},
 };
 
+use crate::animation::{self, TweenCompleted};
 use crate::core::Time;
 use crate::core::debug::DebugInfo;
-use crate::ecs::World;
-use crate::input::Input;
+use crate::core::timer::{self, Scheduler, TimerFinished};
+use crate::ecs::{self, Schedule, World};
+use crate::input::{ActionMap, ImeRequest, Input, InputContextStack, InputHistory};
+use crate::physics::{self, CollisionEvent, ContactForceEvent, JointBroken, Physics, SensorEvent};
 use crate::renderer::Renderer;
 
 /// Engine configuration
@@ -29,6 +33,8 @@ pub struct EngineConfig {
     pub target_fps: u32,
     /// Enable VSync
     pub vsync: bool,
+    /// Fixed simulation timestep in seconds
+    pub fixed_timestep: f32,
 }
 
 impl Default for EngineConfig {
@@ -39,6 +45,7 @@ impl Default for EngineConfig {
             height: 720,
             target_fps: 60,
             vsync: true,
+            fixed_timestep: 1.0 / 60.0,
         }
     }
 }
@@ -68,6 +75,12 @@ impl EngineConfig {
         self.vsync = vsync;
         self
     }
+
+    /// Set the fixed simulation timestep in seconds
+    pub fn with_fixed_timestep(mut self, seconds: f32) -> Self {
+        self.fixed_timestep = seconds;
+        self
+    }
 }
 
 /// Game trait that users implement
@@ -75,6 +88,9 @@ pub trait Game: 'static {
     /// Called once when the engine starts
     fn init(&mut self, engine: &mut EngineContext);
 
+    /// Called at the fixed timestep rate, before `update`
+    fn fixed_update(&mut self, _engine: &mut EngineContext) {}
+
     /// Called every frame for game logic updates
     fn update(&mut self, engine: &mut EngineContext);
 
@@ -94,8 +110,32 @@ pub struct EngineContext {
     pub time: Time,
     /// Input state
     pub input: Input,
+    /// Layered input contexts, read before `actions` and consuming what they use
+    pub input_contexts: InputContextStack,
+    /// Named actions and axes read from `input`
+    pub actions: ActionMap,
+    /// Recent action presses and releases, for buffering and combos
+    pub input_history: InputHistory,
     /// ECS world
     pub world: World,
+    /// Physics simulation, synced with `RigidBody` entities in `world`
+    pub physics: Physics,
+    /// Collisions that started or stopped during this frame's physics steps
+    pub collision_events: Vec<CollisionEvent>,
+    /// Contact forces above their threshold during this frame's physics steps
+    pub contact_force_events: Vec<ContactForceEvent>,
+    /// Joints that broke during this frame's physics steps
+    pub broken_joints: Vec<JointBroken>,
+    /// Sensor enter, stay and exit events from this frame's physics steps
+    pub sensor_events: Vec<SensorEvent>,
+    /// Systems run by the engine every frame, before `Game::update`
+    pub schedule: Schedule,
+    /// Delayed and repeating callbacks, run on game time
+    pub scheduler: Scheduler,
+    /// Timer components that fired this frame
+    pub timer_events: Vec<TimerFinished>,
+    /// Tweens that completed this frame
+    pub tween_events: Vec<TweenCompleted>,
     /// Debug information and stats
     pub debug: DebugInfo,
     /// Renderer (available after initialization)
@@ -107,14 +147,38 @@ pub struct EngineContext {
 }
 
 impl EngineContext {
-    fn new(width: u32, height: u32) -> Self {
+    fn new(config: &EngineConfig) -> Self {
+        let mut time = Time::new();
+        match Duration::try_from_secs_f32(config.fixed_timestep) {
+            Ok(fixed_delta) if config.fixed_timestep > 0.0 => time.set_fixed_delta(fixed_delta),
+            _ => log::warn!(
+                "Invalid fixed timestep {}, using the default",
+                config.fixed_timestep
+            ),
+        }
+
+        let mut world = World::new();
+        physics::register_physics_components(world.registry_mut());
+
         Self {
-            time: Time::new(),
+            time,
             input: Input::new(),
-            world: World::new(),
+            input_contexts: InputContextStack::new(),
+            actions: ActionMap::new(),
+            input_history: InputHistory::new(),
+            world,
+            physics: Physics::new(),
+            collision_events: Vec::new(),
+            contact_force_events: Vec::new(),
+            broken_joints: Vec::new(),
+            sensor_events: Vec::new(),
+            schedule: Schedule::new(),
+            scheduler: Scheduler::new(),
+            timer_events: Vec::new(),
+            tween_events: Vec::new(),
             debug: DebugInfo::new(),
             renderer: None,
-            window_size: PhysicalSize::new(width, height),
+            window_size: PhysicalSize::new(config.width, config.height),
             should_quit: false,
         }
     }
@@ -172,7 +236,7 @@ pub struct Engine<G: Game> {
 impl<G: Game> Engine<G> {
     /// Create a new engine with the given game
     pub fn new(config: EngineConfig, game: G) -> Self {
-        let context = EngineContext::new(config.width, config.height);
+        let context = EngineContext::new(&config);
         Self {
             config,
             game,
@@ -238,23 +302,46 @@ impl<G: Game> ApplicationHandler for Engine<G> {
                 event_loop.exit();
             }
 
-            WindowEvent::Resized(new_size) => {
-                if new_size.width > 0 && new_size.height > 0 {
-                    self.context.window_size = new_size;
-                    if let Some(renderer) = &mut self.context.renderer {
-                        renderer.resize(new_size.width, new_size.height);
-                    }
-                    self.game
-                        .on_resize(&mut self.context, new_size.width, new_size.height);
+            WindowEvent::Resized(new_size) if new_size.width > 0 && new_size.height > 0 => {
+                self.context.window_size = new_size;
+                if let Some(renderer) = &mut self.context.renderer {
+                    renderer.resize(new_size.width, new_size.height);
                 }
+                self.game
+                    .on_resize(&mut self.context, new_size.width, new_size.height);
             }
 
             WindowEvent::KeyboardInput { event, .. } => {
                 if let winit::keyboard::PhysicalKey::Code(key_code) = event.physical_key {
                     self.context.input.process_keyboard(key_code, event.state);
+                    if event.repeat {
+                        self.context.input.process_key_repeat(key_code);
+                    }
+                }
+                if event.state == ElementState::Pressed
+                    && let Some(text) = &event.text
+                {
+                    self.context.input.text_input_mut().process_text(text);
                 }
             }
 
+            WindowEvent::Touch(touch) => {
+                self.context.input.process_touch(
+                    touch.id,
+                    touch.phase,
+                    glam::Vec2::new(touch.location.x as f32, touch.location.y as f32),
+                    touch.force.map(|force| force.normalized() as f32),
+                );
+            }
+
+            WindowEvent::Focused(focused) => {
+                self.context.input.process_focus(focused);
+            }
+
+            WindowEvent::Ime(ime) => {
+                self.context.input.text_input_mut().process_ime(ime);
+            }
+
             WindowEvent::MouseInput { state, button, .. } => {
                 self.context.input.process_mouse_button(button, state);
             }
@@ -278,9 +365,49 @@ impl<G: Game> ApplicationHandler for Engine<G> {
             WindowEvent::RedrawRequested => {
                 // Update time
                 self.context.time.update();
+                self.context
+                    .input
+                    .set_frame_delta(self.context.time.unscaled_delta_seconds());
+                self.context.input.gamepads_mut().poll();
+                self.context
+                    .input
+                    .touches_mut()
+                    .advance(self.context.time.elapsed());
+                self.context.input_contexts.update(&mut self.context.input);
+                self.context.input_history.update(
+                    &self.context.actions,
+                    &self.context.input,
+                    self.context.time.elapsed(),
+                );
 
                 // Update debug stats
-                self.context.debug.record_frame(self.context.time.delta());
+                self.context
+                    .debug
+                    .record_frame(self.context.time.unscaled_delta());
+
+                // Fixed-rate simulation
+                while self.context.time.expend_fixed_step() {
+                    let fixed_dt = self.context.time.fixed_delta_seconds();
+                    ecs::integrate_velocities(&mut self.context.world, fixed_dt);
+                    self.context
+                        .physics
+                        .step_world(&mut self.context.world, fixed_dt);
+                    self.game.fixed_update(&mut self.context);
+                }
+                self.context.collision_events = self.context.physics.drain_collision_events();
+                self.context.contact_force_events =
+                    self.context.physics.drain_contact_force_events();
+                self.context.broken_joints = self.context.physics.drain_broken_joints();
+                self.context.sensor_events = self.context.physics.drain_sensor_events();
+
+                // Registered systems
+                let dt = self.context.time.delta_seconds();
+                self.context.schedule.run(&mut self.context.world, dt);
+                self.context.timer_events = timer::update_timers(&mut self.context.world, dt);
+                self.context
+                    .scheduler
+                    .update(&mut self.context.world, &self.context.time);
+                self.context.tween_events = animation::update_tweens(&mut self.context.world, dt);
 
                 // Update game logic
                 self.game.update(&mut self.context);
@@ -295,6 +422,23 @@ impl<G: Game> ApplicationHandler for Engine<G> {
                 // Render
                 self.game.render(&mut self.context);
 
+                // Apply cursor and IME changes requested by the game
+                if let Some(window) = &self.window {
+                    if let Some(relative) = self.context.input.take_cursor_request() {
+                        apply_relative_mouse_mode(window, relative);
+                    }
+                    for request in self.context.input.text_input_mut().drain_requests() {
+                        match request {
+                            ImeRequest::SetAllowed(allowed) => window.set_ime_allowed(allowed),
+                            ImeRequest::SetCursorArea { position, size } => window
+                                .set_ime_cursor_area(
+                                    PhysicalPosition::new(position.x, position.y),
+                                    PhysicalSize::new(size.x, size.y),
+                                ),
+                        }
+                    }
+                }
+
                 // Clear per-frame input state
                 self.context.input.update();
 
@@ -308,9 +452,40 @@ impl<G: Game> ApplicationHandler for Engine<G> {
         }
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        // Device events arrive even when another window has focus
+        if let DeviceEvent::MouseMotion { delta } = event
+            && self.context.input.is_focused()
+        {
+            self.context
+                .input
+                .process_raw_mouse_motion(glam::Vec2::new(delta.0 as f32, delta.1 as f32));
+        }
+    }
+
     fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
         if let Some(window) = &self.window {
             window.request_redraw();
         }
     }
 }
+
+/// Hide and grab the cursor for mouse look, or restore it
+fn apply_relative_mouse_mode(window: &Window, relative: bool) {
+    if relative {
+        let grabbed = window
+            .set_cursor_grab(CursorGrabMode::Locked)
+            .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined));
+        if let Err(e) = grabbed {
+            log::warn!("Failed to grab cursor: {e}");
+        }
+    } else if let Err(e) = window.set_cursor_grab(CursorGrabMode::None) {
+        log::warn!("Failed to release cursor: {e}");
+    }
+    window.set_cursor_visible(!relative);
+}
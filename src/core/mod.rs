@@ -6,8 +6,10 @@ mod debug;
 mod engine;
 mod scene;
 mod time;
+mod timer;
 
 pub use debug::{DebugInfo, FrameStats};
 pub use engine::{Engine, EngineConfig, EngineContext, Game};
 pub use scene::{Scene, SceneError, SerializedEntity};
 pub use time::Time;
+pub use timer::{Scheduler, Timer, TimerFinished, TimerId, TimerMode, update_timers};
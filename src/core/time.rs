@@ -2,6 +2,14 @@
 
 use std::time::{Duration, Instant};
 
+/// Default fixed timestep (60 Hz)
+const DEFAULT_FIXED_DELTA: Duration = Duration::from_nanos(16_666_667);
+
+/// Maximum number of fixed steps accumulated per frame
+///
+/// Prevents a slow frame from triggering an ever-growing number of catch-up steps.
+const MAX_FIXED_STEPS_PER_FRAME: u32 = 8;
+
 /// Tracks time between frames and total elapsed time
 #[derive(Debug)]
 pub struct Time {
@@ -9,12 +17,22 @@ pub struct Time {
     start_time: Instant,
     /// Time of last frame
     last_frame: Instant,
-    /// Duration of last frame (delta time)
+    /// Duration of last frame, scaled and zero while paused
     delta: Duration,
+    /// Real duration of last frame
+    unscaled_delta: Duration,
+    /// Multiplier applied to game delta time
+    time_scale: f32,
+    /// Whether game time is paused
+    paused: bool,
     /// Total elapsed time since start
     elapsed: Duration,
     /// Frame count
     frame_count: u64,
+    /// Duration of one fixed simulation step
+    fixed_delta: Duration,
+    /// Frame time not yet consumed by fixed steps
+    fixed_accumulator: Duration,
 }
 
 impl Time {
@@ -25,30 +43,128 @@ impl Time {
             start_time: now,
             last_frame: now,
             delta: Duration::ZERO,
+            unscaled_delta: Duration::ZERO,
+            time_scale: 1.0,
+            paused: false,
             elapsed: Duration::ZERO,
             frame_count: 0,
+            fixed_delta: DEFAULT_FIXED_DELTA,
+            fixed_accumulator: Duration::ZERO,
         }
     }
 
     /// Update time at the start of each frame
     pub fn update(&mut self) {
         let now = Instant::now();
-        self.delta = now - self.last_frame;
+        self.unscaled_delta = now - self.last_frame;
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            self.unscaled_delta.mul_f32(self.time_scale)
+        };
         self.last_frame = now;
         self.elapsed = now - self.start_time;
         self.frame_count += 1;
+
+        let max_accumulated = self.fixed_delta * MAX_FIXED_STEPS_PER_FRAME;
+        self.fixed_accumulator = (self.fixed_accumulator + self.delta).min(max_accumulated);
+    }
+
+    /// Consume one fixed step from the accumulator
+    ///
+    /// Returns true while there is enough accumulated time for another step.
+    /// Call in a loop after [`Time::update`] to run fixed-rate systems.
+    pub fn expend_fixed_step(&mut self) -> bool {
+        if self.fixed_delta > Duration::ZERO && self.fixed_accumulator >= self.fixed_delta {
+            self.fixed_accumulator -= self.fixed_delta;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the fixed timestep in seconds
+    pub fn fixed_delta_seconds(&self) -> f32 {
+        self.fixed_delta.as_secs_f32()
+    }
+
+    /// Get the fixed timestep as Duration
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
     }
 
-    /// Get delta time in seconds
+    /// Set the fixed timestep
+    ///
+    /// A zero timestep would stop fixed updates, so it is ignored.
+    pub fn set_fixed_delta(&mut self, fixed_delta: Duration) {
+        if fixed_delta.is_zero() {
+            log::warn!("Ignoring zero fixed timestep");
+            return;
+        }
+        self.fixed_delta = fixed_delta;
+        self.fixed_accumulator = Duration::ZERO;
+    }
+
+    /// Get the interpolation factor between the last two fixed steps (0.0 to 1.0)
+    pub fn fixed_alpha(&self) -> f32 {
+        if self.fixed_delta > Duration::ZERO {
+            self.fixed_accumulator.as_secs_f32() / self.fixed_delta.as_secs_f32()
+        } else {
+            0.0
+        }
+    }
+
+    /// Get delta time in seconds, scaled by the time scale and zero while paused
     pub fn delta_seconds(&self) -> f32 {
         self.delta.as_secs_f32()
     }
 
-    /// Get delta time as Duration
+    /// Get delta time as Duration, scaled by the time scale and zero while paused
     pub fn delta(&self) -> Duration {
         self.delta
     }
 
+    /// Get the real frame time in seconds, ignoring pause and time scale
+    pub fn unscaled_delta_seconds(&self) -> f32 {
+        self.unscaled_delta.as_secs_f32()
+    }
+
+    /// Get the real frame time, ignoring pause and time scale
+    pub fn unscaled_delta(&self) -> Duration {
+        self.unscaled_delta
+    }
+
+    /// Get the time scale
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Set the time scale (1.0 is normal speed, negative values are clamped to 0)
+    ///
+    /// Infinite and NaN scales are ignored.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        if !time_scale.is_finite() {
+            log::warn!("Ignoring non-finite time scale {time_scale}");
+            return;
+        }
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    /// Pause game time
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume game time
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check if game time is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// Get total elapsed time in seconds
     pub fn elapsed_seconds(&self) -> f32 {
         self.elapsed.as_secs_f32()
@@ -66,8 +182,8 @@ impl Time {
 
     /// Get current FPS (averaged over last frame)
     pub fn fps(&self) -> f32 {
-        if self.delta.as_secs_f32() > 0.0 {
-            1.0 / self.delta.as_secs_f32()
+        if self.unscaled_delta.as_secs_f32() > 0.0 {
+            1.0 / self.unscaled_delta.as_secs_f32()
         } else {
             0.0
         }
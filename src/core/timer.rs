@@ -0,0 +1,556 @@
+//! Entity timers and scheduled callbacks
+//!
+//! Both run on game time, so they stop while [`Time`] is paused and follow its
+//! time scale. Work bound to an entity is suspended while the entity is
+//! [`Disabled`] and cancelled once it is despawned or released to an
+//! [`EntityPool`](crate::ecs::EntityPool).
+
+use hecs::Entity;
+
+use super::time::Time;
+use crate::ecs::{Disabled, ReleaseEpoch, World};
+
+/// A scheduled callback
+type ScheduledFn = Box<dyn FnMut(&mut World, Option<Entity>) + Send>;
+
+/// Whether a timer fires once or repeatedly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimerMode {
+    /// Fire once, then stay finished
+    #[default]
+    Once,
+    /// Fire every `duration` seconds
+    Repeating,
+}
+
+/// Emitted when a [`Timer`] component fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerFinished {
+    /// Entity owning the timer
+    pub entity: Entity,
+    /// User-defined timer ID
+    pub id: u64,
+}
+
+/// Countdown timer component
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timer {
+    /// Duration in seconds
+    pub duration: f32,
+    /// Once or repeating
+    pub mode: TimerMode,
+    /// User-defined ID reported in [`TimerFinished`]
+    pub id: u64,
+    /// Time accumulated in the current cycle
+    elapsed: f32,
+    /// Number of times the timer fired on the last tick
+    fired: u32,
+    /// Whether a one-shot timer has finished
+    finished: bool,
+    /// Whether the timer is paused
+    paused: bool,
+}
+
+impl Timer {
+    /// Create a timer
+    #[must_use]
+    pub fn new(duration: f32, mode: TimerMode) -> Self {
+        Self {
+            duration,
+            mode,
+            id: 0,
+            elapsed: 0.0,
+            fired: 0,
+            finished: false,
+            paused: false,
+        }
+    }
+
+    /// Create a one-shot timer
+    #[must_use]
+    pub fn once(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Once)
+    }
+
+    /// Create a repeating timer
+    #[must_use]
+    pub fn repeating(duration: f32) -> Self {
+        Self::new(duration, TimerMode::Repeating)
+    }
+
+    /// Set the ID reported when the timer fires
+    #[must_use]
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Advance the timer and return how many times it fired
+    ///
+    /// Nothing fires for a zero `dt`, e.g. while game time is paused.
+    pub fn tick(&mut self, dt: f32) -> u32 {
+        self.fired = 0;
+        if self.paused || self.finished || dt <= 0.0 {
+            return 0;
+        }
+
+        self.elapsed += dt;
+        if self.elapsed < self.duration {
+            return 0;
+        }
+
+        match self.mode {
+            TimerMode::Once => {
+                self.elapsed = self.duration;
+                self.finished = true;
+                self.fired = 1;
+            }
+            TimerMode::Repeating if self.duration > 0.0 => {
+                self.fired = (self.elapsed / self.duration) as u32;
+                self.elapsed %= self.duration;
+            }
+            TimerMode::Repeating => {
+                self.elapsed = 0.0;
+                self.fired = 1;
+            }
+        }
+        self.fired
+    }
+
+    /// Check if the timer fired on the last tick
+    #[must_use]
+    pub fn just_finished(&self) -> bool {
+        self.fired > 0
+    }
+
+    /// Number of times the timer fired on the last tick
+    #[must_use]
+    pub fn times_fired(&self) -> u32 {
+        self.fired
+    }
+
+    /// Check if a one-shot timer has finished
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Get the progress of the current cycle (0.0 to 1.0)
+    #[must_use]
+    pub fn fraction(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Get the seconds left in the current cycle
+    #[must_use]
+    pub fn remaining(&self) -> f32 {
+        (self.duration - self.elapsed).max(0.0)
+    }
+
+    /// Pause the timer
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume the timer
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check if the timer is paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Restart the timer
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.fired = 0;
+        self.finished = false;
+    }
+}
+
+/// Advance all [`Timer`] components
+///
+/// Returns one event per firing; a repeating timer may fire several times
+/// during a long frame.
+pub fn update_timers(world: &mut World, dt: f32) -> Vec<TimerFinished> {
+    let mut events = Vec::new();
+    for (entity, timer) in world.query_mut::<&mut Timer>().without::<&Disabled>() {
+        for _ in 0..timer.tick(dt) {
+            events.push(TimerFinished {
+                entity,
+                id: timer.id,
+            });
+        }
+    }
+    events
+}
+
+/// Handle to a scheduled callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+/// A pending callback
+struct Task {
+    id: TimerId,
+    /// Entity the task is bound to
+    entity: Option<Entity>,
+    /// Pool release epoch when the task was scheduled
+    epoch: ReleaseEpoch,
+    /// Seconds until the next call
+    remaining: f32,
+    /// Interval for repeating tasks
+    interval: Option<f32>,
+    callback: ScheduledFn,
+}
+
+impl Task {
+    /// Check if the bound entity was despawned or released since scheduling
+    fn is_stale(&self, world: &World) -> bool {
+        self.entity.is_some_and(|entity| {
+            !world.contains(entity)
+                || world
+                    .get::<ReleaseEpoch>(entity)
+                    .is_ok_and(|released| *released > self.epoch)
+        })
+    }
+}
+
+/// Runs callbacks after a delay or at a fixed interval
+///
+/// ```ignore
+/// let door = ctx.world.spawn((Name::new("Door"),));
+/// ctx.scheduler.after_for(door, 2.0, |world, door| {
+///     let _ = world.despawn(door);
+/// });
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+    tasks: Vec<Task>,
+    next_id: u64,
+}
+
+impl Scheduler {
+    /// Create an empty scheduler
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` once after `delay` seconds
+    pub fn after(
+        &mut self,
+        delay: f32,
+        mut callback: impl FnMut(&mut World) + Send + 'static,
+    ) -> TimerId {
+        self.add(None, delay, None, Box::new(move |world, _| callback(world)))
+    }
+
+    /// Call `callback` every `interval` seconds until cancelled
+    pub fn every(
+        &mut self,
+        interval: f32,
+        mut callback: impl FnMut(&mut World) + Send + 'static,
+    ) -> TimerId {
+        self.add(
+            None,
+            interval,
+            Some(interval),
+            Box::new(move |world, _| callback(world)),
+        )
+    }
+
+    /// Call `callback` once after `delay` seconds unless `entity` is despawned
+    /// or released to a pool first
+    pub fn after_for(
+        &mut self,
+        entity: Entity,
+        delay: f32,
+        callback: impl FnMut(&mut World, Entity) + Send + 'static,
+    ) -> TimerId {
+        self.add(Some(entity), delay, None, bind(callback))
+    }
+
+    /// Call `callback` every `interval` seconds until `entity` is despawned or
+    /// released to a pool
+    pub fn every_for(
+        &mut self,
+        entity: Entity,
+        interval: f32,
+        callback: impl FnMut(&mut World, Entity) + Send + 'static,
+    ) -> TimerId {
+        self.add(Some(entity), interval, Some(interval), bind(callback))
+    }
+
+    /// Cancel a scheduled callback, returns true if it was pending
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.tasks.len();
+        self.tasks.retain(|task| task.id != id);
+        self.tasks.len() != len
+    }
+
+    /// Cancel all callbacks bound to an entity
+    pub fn cancel_entity(&mut self, entity: Entity) {
+        self.tasks.retain(|task| task.entity != Some(entity));
+    }
+
+    /// Check if a callback is still pending
+    #[must_use]
+    pub fn is_scheduled(&self, id: TimerId) -> bool {
+        self.tasks.iter().any(|task| task.id == id)
+    }
+
+    /// Get the seconds until a callback runs next
+    #[must_use]
+    pub fn remaining(&self, id: TimerId) -> Option<f32> {
+        self.tasks
+            .iter()
+            .find(|task| task.id == id)
+            .map(|task| task.remaining.max(0.0))
+    }
+
+    /// Cancel all callbacks
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+    }
+
+    /// Get the number of pending callbacks
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Check if no callbacks are pending
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Advance by the frame's game time and run due callbacks
+    pub fn update(&mut self, world: &mut World, time: &Time) {
+        self.tick(world, time.delta_seconds());
+    }
+
+    /// Advance by `dt` seconds and run due callbacks
+    ///
+    /// Nothing runs for a zero `dt`, e.g. while game time is paused. Callbacks
+    /// bound to [`Disabled`] entities wait until the entity is enabled again;
+    /// callbacks bound to despawned or released entities are dropped without
+    /// running.
+    pub fn tick(&mut self, world: &mut World, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        self.tasks.retain_mut(|task| {
+            if task.is_stale(world) {
+                return false;
+            }
+            if task
+                .entity
+                .is_some_and(|entity| world.get::<Disabled>(entity).is_ok())
+            {
+                return true;
+            }
+            task.remaining -= dt;
+            while task.remaining <= 0.0 {
+                if task.is_stale(world) {
+                    return false;
+                }
+                (task.callback)(world, task.entity);
+
+                match task.interval {
+                    // Zero intervals run once per tick
+                    Some(interval) if interval > 0.0 => task.remaining += interval,
+                    Some(_) => {
+                        task.remaining = 0.0;
+                        return true;
+                    }
+                    None => return false,
+                }
+            }
+            true
+        });
+
+        // Callbacks may have despawned or released entities other tasks are bound to
+        self.tasks.retain(|task| !task.is_stale(world));
+    }
+
+    fn add(
+        &mut self,
+        entity: Option<Entity>,
+        delay: f32,
+        interval: Option<f32>,
+        callback: ScheduledFn,
+    ) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.tasks.push(Task {
+            id,
+            entity,
+            epoch: ReleaseEpoch::current(),
+            remaining: delay,
+            interval,
+            callback,
+        });
+        id
+    }
+}
+
+impl std::fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("tasks", &self.tasks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Wrap an entity callback; only called with `Some` for entity-bound tasks
+fn bind(mut callback: impl FnMut(&mut World, Entity) + Send + 'static) -> ScheduledFn {
+    Box::new(move |world, entity| {
+        if let Some(entity) = entity {
+            callback(world, entity);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{EntityPool, Name, Template};
+    use hecs::EntityBuilderClone;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_timer_component() {
+        let mut world = World::new();
+        let once = world.spawn((Timer::once(1.0).with_id(1),));
+        let repeating = world.spawn((Timer::repeating(0.5).with_id(2),));
+
+        assert!(update_timers(&mut world, 0.75).iter().all(|e| e.id == 2));
+
+        let events = update_timers(&mut world, 1.0);
+        assert_eq!(events.iter().filter(|e| e.entity == repeating).count(), 2);
+        assert_eq!(events.iter().filter(|e| e.entity == once).count(), 1);
+        assert!(world.get::<Timer>(once).unwrap().is_finished());
+
+        assert!(update_timers(&mut world, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_after_and_every() {
+        let mut world = World::new();
+        let mut scheduler = Scheduler::new();
+        let count = Arc::new(AtomicU32::new(0));
+
+        let counter = Arc::clone(&count);
+        let every = scheduler.every(1.0, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        let once = scheduler.after(1.5, |world| {
+            world.spawn((Name::new("Spawned"),));
+        });
+
+        scheduler.tick(&mut world, 1.0);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+        assert!(world.find_by_name("Spawned").is_none());
+
+        scheduler.tick(&mut world, 1.0);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+        assert!(world.find_by_name("Spawned").is_some());
+        assert!(!scheduler.is_scheduled(once));
+
+        assert!(scheduler.cancel(every));
+        scheduler.tick(&mut world, 5.0);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_entity_tasks_cancelled_on_despawn() {
+        let mut world = World::new();
+        let mut scheduler = Scheduler::new();
+        let entity = world.spawn((Name::new("Turret"),));
+
+        let count = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&count);
+        scheduler.every_for(entity, 0.5, move |_, _| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        scheduler.after_for(entity, 0.75, |world, entity| {
+            let _ = world.despawn(entity);
+        });
+
+        scheduler.tick(&mut world, 0.5);
+        scheduler.tick(&mut world, 0.5);
+        assert!(!world.contains(entity));
+        assert!(scheduler.is_empty());
+
+        scheduler.tick(&mut world, 0.5);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_paused_time_and_disabled_entities() {
+        let mut world = World::new();
+        let mut timer = Timer::repeating(0.0);
+        assert_eq!(timer.tick(0.0), 0);
+        assert_eq!(timer.tick(0.1), 1);
+
+        let mut scheduler = Scheduler::new();
+        let count = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&count);
+        scheduler.every(0.0, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        scheduler.tick(&mut world, 0.0);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        let pooled = world.spawn((Name::new("Pooled"), Disabled));
+        let counter = Arc::clone(&count);
+        scheduler.after_for(pooled, 0.5, move |_, _| {
+            counter.fetch_add(10, Ordering::Relaxed);
+        });
+        scheduler.tick(&mut world, 1.0);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        world.remove_one::<Disabled>(pooled).unwrap();
+        scheduler.tick(&mut world, 0.25);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+        scheduler.tick(&mut world, 0.25);
+        assert_eq!(count.load(Ordering::Relaxed), 13);
+    }
+
+    #[test]
+    fn test_pool_release_cancels_entity_tasks() {
+        let mut world = World::new();
+        let mut builder = EntityBuilderClone::new();
+        builder.add(Name::new("Bullet"));
+        let mut pool = EntityPool::new(Template::new(builder));
+        let mut scheduler = Scheduler::new();
+        let count = Arc::new(AtomicU32::new(0));
+
+        let bullet = pool.acquire(&mut world).unwrap();
+        let counter = Arc::clone(&count);
+        let old = scheduler.after_for(bullet, 1.0, move |_, _| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        pool.release(&mut world, bullet);
+        assert_eq!(pool.acquire(&mut world), Some(bullet));
+
+        // Scheduled for the new user of the same entity
+        let counter = Arc::clone(&count);
+        let new = scheduler.after_for(bullet, 1.0, move |_, _| {
+            counter.fetch_add(10, Ordering::Relaxed);
+        });
+        scheduler.tick(&mut world, 1.0);
+        assert_eq!(count.load(Ordering::Relaxed), 10);
+        assert!(!scheduler.is_scheduled(old));
+        assert!(!scheduler.is_scheduled(new));
+    }
+}
@@ -97,12 +97,80 @@ pub struct Velocity {
     pub angular: Vec3,
 }
 
-/// Name component for debugging
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Name component for debugging and lookup
+///
+/// Named entities can be found with [`World::find_by_name`](super::World::find_by_name).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Name(pub String);
 
 impl Name {
     pub fn new(name: impl Into<String>) -> Self {
         Self(name.into())
     }
+
+    /// Get the name as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Tag set component for lightweight entity grouping
+///
+/// Tagged entities can be listed with [`World::tagged`](super::World::tagged).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags(pub Vec<String>);
+
+impl Tags {
+    /// Create an empty tag set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tag (builder style)
+    #[must_use]
+    pub fn with(mut self, tag: impl Into<String>) -> Self {
+        self.insert(tag);
+        self
+    }
+
+    /// Add a tag, returns false if it was already present
+    pub fn insert(&mut self, tag: impl Into<String>) -> bool {
+        let tag = tag.into();
+        if self.contains(&tag) {
+            false
+        } else {
+            self.0.push(tag);
+            true
+        }
+    }
+
+    /// Remove a tag, returns true if it was present
+    pub fn remove(&mut self, tag: &str) -> bool {
+        if let Some(pos) = self.0.iter().position(|t| t == tag) {
+            self.0.remove(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check if a tag is present
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|t| t == tag)
+    }
+
+    /// Iterate over tags
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Check if there are no tags
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the number of tags
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
 }
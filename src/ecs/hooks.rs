@@ -0,0 +1,218 @@
+//! Component lifecycle hooks
+//!
+//! Hooks are registered per component type and invoked by [`World`] when that
+//! component is added, inserted, removed, or its entity is despawned. Hooks get
+//! shared access to the world and a [`Commands`] queue that is applied once the
+//! triggering operation has finished.
+
+use std::any::TypeId;
+use std::sync::Arc;
+
+use hecs::Entity;
+use rustc_hash::FxHashMap;
+
+use super::schedule::Commands;
+use super::world::World;
+
+/// Callback invoked for a component lifecycle event
+pub type ComponentHook = Arc<dyn Fn(&World, Entity, &mut Commands) + Send + Sync>;
+
+/// Lifecycle event that triggers a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HookKind {
+    /// Component added to an entity that did not have it
+    Add,
+    /// Component added or replaced
+    Insert,
+    /// Component about to be removed (also fired on despawn)
+    Remove,
+    /// Entity with the component about to be despawned
+    Despawn,
+}
+
+/// Hooks registered for one component type
+#[derive(Default, Clone)]
+struct ComponentHooks {
+    on_add: Vec<ComponentHook>,
+    on_insert: Vec<ComponentHook>,
+    on_remove: Vec<ComponentHook>,
+    on_despawn: Vec<ComponentHook>,
+}
+
+impl ComponentHooks {
+    fn get(&self, kind: HookKind) -> &[ComponentHook] {
+        match kind {
+            HookKind::Add => &self.on_add,
+            HookKind::Insert => &self.on_insert,
+            HookKind::Remove => &self.on_remove,
+            HookKind::Despawn => &self.on_despawn,
+        }
+    }
+
+    fn get_mut(&mut self, kind: HookKind) -> &mut Vec<ComponentHook> {
+        match kind {
+            HookKind::Add => &mut self.on_add,
+            HookKind::Insert => &mut self.on_insert,
+            HookKind::Remove => &mut self.on_remove,
+            HookKind::Despawn => &mut self.on_despawn,
+        }
+    }
+}
+
+/// All registered lifecycle hooks, keyed by component type
+#[derive(Default, Clone)]
+pub(super) struct Hooks {
+    by_type: FxHashMap<TypeId, ComponentHooks>,
+}
+
+impl Hooks {
+    /// Check if no hooks are registered
+    pub(super) fn is_empty(&self) -> bool {
+        self.by_type.is_empty()
+    }
+
+    /// Filter a list of component types to those with hooks
+    pub(super) fn hooked(&self, types: impl IntoIterator<Item = TypeId>) -> Vec<TypeId> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        types
+            .into_iter()
+            .filter(|id| self.by_type.contains_key(id))
+            .collect()
+    }
+
+    /// Collect hooks of one kind for the given component types
+    pub(super) fn collect(&self, types: &[TypeId], kind: HookKind) -> Vec<ComponentHook> {
+        types
+            .iter()
+            .filter_map(|id| self.by_type.get(id))
+            .flat_map(|hooks| hooks.get(kind).iter().cloned())
+            .collect()
+    }
+
+    fn add(&mut self, type_id: TypeId, kind: HookKind, hook: ComponentHook) {
+        self.by_type
+            .entry(type_id)
+            .or_default()
+            .get_mut(kind)
+            .push(hook);
+    }
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("types", &self.by_type.len())
+            .finish()
+    }
+}
+
+impl World {
+    /// Register a hook for when `T` is added to an entity that did not have it
+    pub fn on_add<T: hecs::Component>(
+        &mut self,
+        hook: impl Fn(&World, Entity, &mut Commands) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .add(TypeId::of::<T>(), HookKind::Add, Arc::new(hook));
+    }
+
+    /// Register a hook for when `T` is added or replaced
+    pub fn on_insert<T: hecs::Component>(
+        &mut self,
+        hook: impl Fn(&World, Entity, &mut Commands) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .add(TypeId::of::<T>(), HookKind::Insert, Arc::new(hook));
+    }
+
+    /// Register a hook for just before `T` is removed, including on despawn
+    pub fn on_remove<T: hecs::Component>(
+        &mut self,
+        hook: impl Fn(&World, Entity, &mut Commands) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .add(TypeId::of::<T>(), HookKind::Remove, Arc::new(hook));
+    }
+
+    /// Register a hook for just before an entity with `T` is despawned
+    pub fn on_despawn<T: hecs::Component>(
+        &mut self,
+        hook: impl Fn(&World, Entity, &mut Commands) + Send + Sync + 'static,
+    ) {
+        self.hooks
+            .add(TypeId::of::<T>(), HookKind::Despawn, Arc::new(hook));
+    }
+
+    /// Run hooks of one kind for the given component types
+    pub(super) fn run_hooks(
+        &self,
+        entity: Entity,
+        types: &[TypeId],
+        kind: HookKind,
+        commands: &mut Commands,
+    ) {
+        for hook in self.hooks.collect(types, kind) {
+            hook(self, entity, commands);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Name, Transform, Velocity};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_add_insert_remove_order() {
+        let mut world = World::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        for (kind, label) in [
+            (HookKind::Add, "add"),
+            (HookKind::Insert, "insert"),
+            (HookKind::Remove, "remove"),
+            (HookKind::Despawn, "despawn"),
+        ] {
+            let log = Arc::clone(&log);
+            let hook = move |_: &World, _: Entity, _: &mut Commands| {
+                log.lock().unwrap().push(label);
+            };
+            match kind {
+                HookKind::Add => world.on_add::<Velocity>(hook),
+                HookKind::Insert => world.on_insert::<Velocity>(hook),
+                HookKind::Remove => world.on_remove::<Velocity>(hook),
+                HookKind::Despawn => world.on_despawn::<Velocity>(hook),
+            }
+        }
+
+        let entity = world.spawn((Transform::default(), Velocity::default()));
+        world.insert_one(entity, Velocity::default()).unwrap();
+        world.remove_one::<Velocity>(entity).unwrap();
+        world.insert_one(entity, Velocity::default()).unwrap();
+        world.despawn(entity).unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "add", "insert", "insert", "remove", "add", "insert", "remove", "despawn"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hook_commands_are_applied() {
+        let mut world = World::new();
+        world.on_despawn::<Name>(|world, entity, commands| {
+            let name = world.get::<Name>(entity).unwrap().0.clone();
+            commands.spawn((Name::new(format!("{name} (corpse)")),));
+        });
+
+        let entity = world.spawn((Name::new("Goblin"),));
+        world.despawn(entity).unwrap();
+
+        assert!(world.find_by_name("Goblin (corpse)").is_some());
+    }
+}
@@ -4,8 +4,25 @@
 
 mod components;
 mod hierarchy;
+mod hooks;
+mod motion;
+mod pool;
+mod reflect;
+mod schedule;
+mod spatial;
+mod template;
 mod world;
 
-pub use components::{Name, Transform, Velocity};
+pub use components::{Name, Tags, Transform, Velocity};
 pub use hierarchy::{Children, GlobalTransform, Parent};
+pub use hooks::ComponentHook;
+pub use motion::{MotionSettings, PhysicsDriven, integrate_velocities};
+pub use pool::{Disabled, EntityPool, PoolStats, ReleaseEpoch};
+pub use reflect::{
+    CloneFn, ComponentRegistration, FieldInfo, Reflect, ReflectError, ReflectedComponent,
+    TypeRegistry, Value, ValueKind,
+};
+pub use schedule::{Commands, Schedule, System, SystemAccess};
+pub use spatial::SpatialGrid;
+pub use template::Template;
 pub use world::World;
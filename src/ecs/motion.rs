@@ -0,0 +1,180 @@
+//! Kinematic motion system
+//!
+//! Integrates [`Velocity`] into [`Transform`] for entities that are not
+//! driven by the physics simulation.
+
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use super::components::{Transform, Velocity};
+use super::pool::Disabled;
+use super::world::World;
+use crate::physics::RigidBody;
+
+/// Optional damping and speed limits for kinematic motion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MotionSettings {
+    /// Linear damping coefficient (velocity loss per second)
+    pub linear_damping: f32,
+    /// Angular damping coefficient (velocity loss per second)
+    pub angular_damping: f32,
+    /// Maximum linear speed (`None` for unlimited)
+    pub max_linear_speed: Option<f32>,
+    /// Maximum angular speed in radians per second (`None` for unlimited)
+    pub max_angular_speed: Option<f32>,
+}
+
+impl MotionSettings {
+    /// Create settings with no damping and no speed limits
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            max_linear_speed: None,
+            max_angular_speed: None,
+        }
+    }
+
+    /// Set linear and angular damping
+    #[must_use]
+    pub const fn with_damping(mut self, linear: f32, angular: f32) -> Self {
+        self.linear_damping = linear;
+        self.angular_damping = angular;
+        self
+    }
+
+    /// Set the maximum linear speed
+    #[must_use]
+    pub const fn with_max_linear_speed(mut self, speed: f32) -> Self {
+        self.max_linear_speed = Some(speed);
+        self
+    }
+
+    /// Set the maximum angular speed
+    #[must_use]
+    pub const fn with_max_angular_speed(mut self, speed: f32) -> Self {
+        self.max_angular_speed = Some(speed);
+        self
+    }
+
+    /// Apply damping and speed limits to a velocity
+    fn apply(&self, velocity: &mut Velocity, dt: f32) {
+        // Same damping model as rapier, stable for any dt
+        velocity.linear *= 1.0 / (1.0 + self.linear_damping.max(0.0) * dt);
+        velocity.angular *= 1.0 / (1.0 + self.angular_damping.max(0.0) * dt);
+
+        if let Some(max) = self.max_linear_speed {
+            velocity.linear = velocity.linear.clamp_length_max(max.max(0.0));
+        }
+        if let Some(max) = self.max_angular_speed {
+            velocity.angular = velocity.angular.clamp_length_max(max.max(0.0));
+        }
+    }
+}
+
+impl Default for MotionSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Marker for entities whose transform is owned by the physics simulation.
+///
+/// Entities with this component are skipped by [`integrate_velocities`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhysicsDriven;
+
+/// Integrate linear and angular velocity into transforms.
+///
+/// Entities with a [`RigidBody`], or marked [`PhysicsDriven`] or
+/// [`Disabled`], are skipped.
+///
+/// The engine runs this on the fixed timestep; call it manually when
+/// stepping a world outside of the engine loop.
+pub fn integrate_velocities(world: &mut World, dt: f32) {
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (_, (transform, velocity, settings)) in world
+        .query_mut::<(&mut Transform, &mut Velocity, Option<&MotionSettings>)>()
+        .without::<hecs::Or<&RigidBody, hecs::Or<&PhysicsDriven, &Disabled>>>()
+    {
+        if let Some(settings) = settings {
+            settings.apply(velocity, dt);
+        }
+
+        transform.position += velocity.linear * dt;
+
+        let rotation = velocity.angular * dt;
+        if rotation != Vec3::ZERO {
+            transform.rotation =
+                (Quat::from_scaled_axis(rotation) * transform.rotation).normalize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integrate_linear_and_angular() {
+        let mut world = World::new();
+        let entity = world.spawn((
+            Transform::default(),
+            Velocity {
+                linear: Vec3::new(2.0, 0.0, 0.0),
+                angular: Vec3::new(0.0, std::f32::consts::PI, 0.0),
+            },
+        ));
+
+        integrate_velocities(&mut world, 0.5);
+
+        let transform = world.get::<Transform>(entity).unwrap();
+        assert!((transform.position.x - 1.0).abs() < 0.001);
+        let expected = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        assert!(transform.rotation.angle_between(expected) < 0.001);
+    }
+
+    #[test]
+    fn test_damping_and_max_speed() {
+        let mut world = World::new();
+        let entity = world.spawn((
+            Transform::default(),
+            Velocity {
+                linear: Vec3::new(10.0, 0.0, 0.0),
+                angular: Vec3::ZERO,
+            },
+            MotionSettings::new()
+                .with_damping(1.0, 0.0)
+                .with_max_linear_speed(4.0),
+        ));
+
+        integrate_velocities(&mut world, 1.0);
+
+        let velocity = *world.get::<Velocity>(entity).unwrap();
+        assert!((velocity.linear.length() - 4.0).abs() < 0.001);
+        let transform = world.get::<Transform>(entity).unwrap();
+        assert!((transform.position.x - 4.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_physics_driven_skipped() {
+        let mut world = World::new();
+        let entity = world.spawn((
+            Transform::default(),
+            Velocity {
+                linear: Vec3::ONE,
+                angular: Vec3::ZERO,
+            },
+            PhysicsDriven,
+        ));
+
+        integrate_velocities(&mut world, 1.0);
+
+        let transform = world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.position, Vec3::ZERO);
+    }
+}
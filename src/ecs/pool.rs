@@ -0,0 +1,296 @@
+//! Entity pooling
+//!
+//! Reuses entities for short-lived objects such as bullets instead of spawning
+//! and despawning them every time.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use hecs::Entity;
+use rustc_hash::FxHashSet;
+
+use super::template::Template;
+use super::world::World;
+
+/// Marker for entities that are parked and should be ignored by systems
+///
+/// Pooled entities carry this while they are not in use. Built-in systems
+/// such as velocity integration, the spatial grid, tweens and timers skip
+/// disabled entities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Disabled;
+
+/// Number of pool releases so far, shared by all pools and worlds
+static RELEASES: AtomicU64 = AtomicU64::new(0);
+
+/// Records when a pooled entity was last released
+///
+/// Entity-bound work that outlives a release, such as
+/// [`Scheduler`](crate::core::Scheduler) tasks, compares this with
+/// [`ReleaseEpoch::current`] taken when it was scheduled and is dropped if the
+/// entity was released since, so it never runs for the entity's next user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseEpoch(u64);
+
+impl ReleaseEpoch {
+    /// Get the epoch of the most recent release
+    #[must_use]
+    pub fn current() -> Self {
+        Self(RELEASES.load(Ordering::Relaxed))
+    }
+
+    fn next() -> Self {
+        Self(RELEASES.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+/// Usage statistics for an [`EntityPool`]
+///
+/// Displays as a single line suitable for
+/// [`DebugInfo::add_line`](crate::core::DebugInfo::add_line).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Entities currently handed out
+    pub active: usize,
+    /// Disabled entities ready for reuse
+    pub available: usize,
+    /// Highest number of entities active at once
+    pub peak_active: usize,
+    /// Entities spawned by the pool in total
+    pub spawned: usize,
+    /// Successful acquires
+    pub acquired: u64,
+    /// Acquires that failed because the pool was at its maximum size
+    pub exhausted: u64,
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} active | {} free | peak {} | spawned {}",
+            self.active, self.available, self.peak_active, self.spawned
+        )?;
+        if self.exhausted > 0 {
+            write!(f, " | exhausted {}", self.exhausted)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pool of entities spawned from a template
+///
+/// Only the root components of the template are used. Acquiring an entity
+/// re-inserts those components, resetting them to the template values;
+/// components added while the entity was in use are kept.
+#[derive(Debug)]
+pub struct EntityPool {
+    template: Template,
+    /// Disabled entities ready for reuse
+    available: Vec<Entity>,
+    /// Entities currently handed out
+    active: FxHashSet<Entity>,
+    /// Maximum number of entities the pool will spawn
+    max_size: Option<usize>,
+    stats: PoolStats,
+}
+
+impl EntityPool {
+    /// Create an empty pool
+    #[must_use]
+    pub fn new(template: Template) -> Self {
+        Self {
+            template,
+            available: Vec::new(),
+            active: FxHashSet::default(),
+            max_size: None,
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// Limit the number of entities the pool will spawn
+    #[must_use]
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Spawn disabled entities until `count` are available
+    pub fn prewarm(&mut self, world: &mut World, count: usize) {
+        while self.available.len() < count && self.can_grow() {
+            let entity = self.spawn(world);
+            let _ = world.insert_one(entity, Disabled);
+            self.available.push(entity);
+        }
+        self.refresh_counts();
+    }
+
+    /// Take an entity from the pool, spawning a new one if none are free
+    ///
+    /// Returns `None` if the pool is at its maximum size.
+    pub fn acquire(&mut self, world: &mut World) -> Option<Entity> {
+        let reused = loop {
+            match self.available.pop() {
+                // Skip entities despawned behind the pool's back
+                Some(entity) if !world.contains(entity) => {}
+                Some(entity) => break Some(entity),
+                None => break None,
+            }
+        };
+
+        let entity = match reused {
+            Some(entity) => {
+                let _ = world.insert(entity, self.template.components());
+                let _ = world.remove_one::<Disabled>(entity);
+                entity
+            }
+            None if self.can_grow() => self.spawn(world),
+            None => {
+                self.stats.exhausted += 1;
+                self.refresh_counts();
+                return None;
+            }
+        };
+
+        self.active.insert(entity);
+        self.stats.acquired += 1;
+        self.refresh_counts();
+        Some(entity)
+    }
+
+    /// Return an entity to the pool and disable it
+    ///
+    /// The entity is stamped with a new [`ReleaseEpoch`], which cancels
+    /// [`Scheduler`](crate::core::Scheduler) tasks bound to it so they don't
+    /// fire once the entity is acquired again. Returns false if the entity was
+    /// not handed out by this pool.
+    pub fn release(&mut self, world: &mut World, entity: Entity) -> bool {
+        if !self.active.remove(&entity) {
+            return false;
+        }
+        if world
+            .insert(entity, (Disabled, ReleaseEpoch::next()))
+            .is_ok()
+        {
+            self.available.push(entity);
+        }
+        self.refresh_counts();
+        true
+    }
+
+    /// Return all active entities to the pool
+    pub fn release_all(&mut self, world: &mut World) {
+        let active: Vec<Entity> = self.active.iter().copied().collect();
+        for entity in active {
+            self.release(world, entity);
+        }
+    }
+
+    /// Check if an entity is currently handed out by this pool
+    #[must_use]
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.active.contains(&entity)
+    }
+
+    /// Iterate over the active entities
+    pub fn active(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.active.iter().copied()
+    }
+
+    /// Despawn every entity owned by the pool
+    pub fn clear(&mut self, world: &mut World) {
+        for entity in self.available.drain(..).chain(self.active.drain()) {
+            let _ = world.despawn(entity);
+        }
+        self.refresh_counts();
+    }
+
+    /// Get the pool statistics
+    #[must_use]
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    /// Get the pool template
+    #[must_use]
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    fn can_grow(&self) -> bool {
+        self.max_size
+            .is_none_or(|max| self.active.len() + self.available.len() < max)
+    }
+
+    fn spawn(&mut self, world: &mut World) -> Entity {
+        self.stats.spawned += 1;
+        world.spawn(self.template.components())
+    }
+
+    fn refresh_counts(&mut self) {
+        self.stats.active = self.active.len();
+        self.stats.available = self.available.len();
+        self.stats.peak_active = self.stats.peak_active.max(self.stats.active);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Transform, Velocity, integrate_velocities};
+    use glam::Vec3;
+    use hecs::EntityBuilderClone;
+
+    fn bullet_pool() -> EntityPool {
+        let mut builder = EntityBuilderClone::new();
+        builder.add(Transform::default()).add(Velocity {
+            linear: Vec3::X,
+            angular: Vec3::ZERO,
+        });
+        EntityPool::new(Template::new(builder))
+    }
+
+    #[test]
+    fn test_acquire_resets_components() {
+        let mut world = World::new();
+        let mut pool = bullet_pool();
+        pool.prewarm(&mut world, 4);
+        assert_eq!(world.len(), 4);
+
+        let bullet = pool.acquire(&mut world).unwrap();
+        integrate_velocities(&mut world, 1.0);
+        assert_eq!(world.get::<Transform>(bullet).unwrap().position, Vec3::X);
+
+        assert!(pool.release(&mut world, bullet));
+        assert!(world.get::<Disabled>(bullet).is_ok());
+        integrate_velocities(&mut world, 1.0);
+        assert_eq!(world.get::<Transform>(bullet).unwrap().position, Vec3::X);
+
+        let again = pool.acquire(&mut world).unwrap();
+        assert_eq!(again, bullet);
+        assert_eq!(world.get::<Transform>(again).unwrap().position, Vec3::ZERO);
+        assert!(world.get::<Disabled>(again).is_err());
+        assert_eq!(world.len(), 4);
+    }
+
+    #[test]
+    fn test_max_size_and_stats() {
+        let mut world = World::new();
+        let mut pool = bullet_pool().with_max_size(2);
+
+        let a = pool.acquire(&mut world).unwrap();
+        pool.acquire(&mut world).unwrap();
+        assert!(pool.acquire(&mut world).is_none());
+        let stranger = world.spawn((Transform::default(),));
+        assert!(!pool.release(&mut world, stranger));
+        pool.release(&mut world, a);
+
+        let stats = pool.stats();
+        assert_eq!(stats.active, 1);
+        assert_eq!(stats.available, 1);
+        assert_eq!(stats.peak_active, 2);
+        assert_eq!(stats.spawned, 2);
+        assert_eq!(stats.exhausted, 1);
+        assert!(stats.to_string().contains("exhausted 1"));
+    }
+}
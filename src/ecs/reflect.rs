@@ -0,0 +1,601 @@
+//! Runtime component reflection
+//!
+//! Lets tooling enumerate, read and modify component fields without knowing
+//! the concrete component types.
+
+use std::any::TypeId;
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+use hecs::Entity;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use super::components::{Name, Tags, Transform, Velocity};
+use super::hierarchy::GlobalTransform;
+use super::motion::{MotionSettings, PhysicsDriven};
+
+/// A dynamically typed field value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Value {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    String(String),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Quat(Quat),
+}
+
+/// The type of a [`Value`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Bool,
+    I32,
+    U32,
+    F32,
+    String,
+    Vec2,
+    Vec3,
+    Vec4,
+    Quat,
+}
+
+impl Value {
+    /// Get the type of this value
+    #[must_use]
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Self::Bool(_) => ValueKind::Bool,
+            Self::I32(_) => ValueKind::I32,
+            Self::U32(_) => ValueKind::U32,
+            Self::F32(_) => ValueKind::F32,
+            Self::String(_) => ValueKind::String,
+            Self::Vec2(_) => ValueKind::Vec2,
+            Self::Vec3(_) => ValueKind::Vec3,
+            Self::Vec4(_) => ValueKind::Vec4,
+            Self::Quat(_) => ValueKind::Quat,
+        }
+    }
+
+    /// Get a scalar component (`x`, `y`, `z` or `w`) of a vector or quaternion value
+    #[must_use]
+    pub fn component(&self, name: &str) -> Option<Value> {
+        let index = component_index(name)?;
+        let values: &[f32] = match self {
+            Self::Vec2(v) => &v.to_array(),
+            Self::Vec3(v) => &v.to_array(),
+            Self::Vec4(v) => &v.to_array(),
+            Self::Quat(q) => &q.to_array(),
+            _ => return None,
+        };
+        values.get(index).copied().map(Value::F32)
+    }
+
+    /// Replace a scalar component (`x`, `y`, `z` or `w`) of a vector or quaternion value
+    ///
+    /// Quaternions are not renormalized, so several components can be set one
+    /// after another; normalize once all of them are written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component does not exist or `value` is not an `F32`
+    pub fn set_component(&mut self, name: &str, value: Value) -> Result<(), ReflectError> {
+        let Value::F32(scalar) = value else {
+            return Err(ReflectError::TypeMismatch {
+                expected: ValueKind::F32,
+                found: value.kind(),
+            });
+        };
+        let unknown = || ReflectError::UnknownField(name.to_owned());
+        let index = component_index(name).ok_or_else(unknown)?;
+
+        let slot = match self {
+            Self::Vec2(v) => v.as_mut().get_mut(index),
+            Self::Vec3(v) => v.as_mut().get_mut(index),
+            Self::Vec4(v) => v.as_mut().get_mut(index),
+            Self::Quat(q) => {
+                let mut array = q.to_array();
+                let slot = array.get_mut(index).ok_or_else(unknown)?;
+                *slot = scalar;
+                *q = Quat::from_array(array);
+                return Ok(());
+            }
+            _ => None,
+        };
+        *slot.ok_or_else(unknown)? = scalar;
+        Ok(())
+    }
+}
+
+fn component_index(name: &str) -> Option<usize> {
+    match name {
+        "x" => Some(0),
+        "y" => Some(1),
+        "z" => Some(2),
+        "w" => Some(3),
+        _ => None,
+    }
+}
+
+/// Description of a reflected field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Field name
+    pub name: &'static str,
+    /// Field type
+    pub kind: ValueKind,
+}
+
+/// Trait for components whose fields can be accessed at runtime
+///
+/// Implement it with [`impl_reflect!`](crate::impl_reflect) for plain structs.
+pub trait Reflect: hecs::Component + Default {
+    /// Stable, human-readable component name
+    fn type_name() -> &'static str;
+
+    /// All reflected fields
+    fn fields() -> &'static [FieldInfo];
+
+    /// Read a field by name
+    fn field(&self, name: &str) -> Option<Value>;
+
+    /// Write a field by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field does not exist or the value has the wrong type
+    fn set_field(&mut self, name: &str, value: Value) -> Result<(), ReflectError>;
+}
+
+/// Implement [`Reflect`] for a struct with named fields
+///
+/// ```ignore
+/// impl_reflect!(Health, "Health" { current: F32, max: F32 });
+/// ```
+#[macro_export]
+macro_rules! impl_reflect {
+    ($ty:ty, $name:literal { $($field:ident : $kind:ident),* $(,)? }) => {
+        impl $crate::ecs::Reflect for $ty {
+            fn type_name() -> &'static str {
+                $name
+            }
+
+            fn fields() -> &'static [$crate::ecs::FieldInfo] {
+                &[$($crate::ecs::FieldInfo {
+                    name: stringify!($field),
+                    kind: $crate::ecs::ValueKind::$kind,
+                }),*]
+            }
+
+            fn field(&self, name: &str) -> Option<$crate::ecs::Value> {
+                match name {
+                    $(stringify!($field) => Some($crate::ecs::Value::$kind(self.$field.clone())),)*
+                    _ => None,
+                }
+            }
+
+            fn set_field(
+                &mut self,
+                name: &str,
+                value: $crate::ecs::Value,
+            ) -> Result<(), $crate::ecs::ReflectError> {
+                match (name, value) {
+                    $((stringify!($field), $crate::ecs::Value::$kind(v)) => {
+                        self.$field = v;
+                        Ok(())
+                    })*
+                    $((stringify!($field), other) => Err($crate::ecs::ReflectError::TypeMismatch {
+                        expected: $crate::ecs::ValueKind::$kind,
+                        found: other.kind(),
+                    }),)*
+                    (name, _) => Err($crate::ecs::ReflectError::UnknownField(name.to_owned())),
+                }
+            }
+        }
+    };
+}
+
+crate::impl_reflect!(Transform, "Transform" {
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+});
+
+crate::impl_reflect!(Velocity, "Velocity" {
+    linear: Vec3,
+    angular: Vec3,
+});
+
+impl Reflect for Name {
+    fn type_name() -> &'static str {
+        "Name"
+    }
+
+    fn fields() -> &'static [FieldInfo] {
+        &[FieldInfo {
+            name: "name",
+            kind: ValueKind::String,
+        }]
+    }
+
+    fn field(&self, name: &str) -> Option<Value> {
+        (name == "name").then(|| Value::String(self.0.clone()))
+    }
+
+    fn set_field(&mut self, name: &str, value: Value) -> Result<(), ReflectError> {
+        match (name, value) {
+            ("name", Value::String(s)) => {
+                self.0 = s;
+                Ok(())
+            }
+            ("name", other) => Err(ReflectError::TypeMismatch {
+                expected: ValueKind::String,
+                found: other.kind(),
+            }),
+            (name, _) => Err(ReflectError::UnknownField(name.to_owned())),
+        }
+    }
+}
+
+/// Read a value by dotted path, e.g. `"position.x"`
+fn get_path<T: Reflect>(component: &T, path: &str) -> Result<Value, ReflectError> {
+    let mut segments = path.split('.');
+    let field = segments.next().unwrap_or_default();
+    let mut value = component
+        .field(field)
+        .ok_or_else(|| ReflectError::UnknownField(field.to_owned()))?;
+
+    for segment in segments {
+        value = value
+            .component(segment)
+            .ok_or_else(|| ReflectError::UnknownField(path.to_owned()))?;
+    }
+    Ok(value)
+}
+
+/// Write a value by dotted path, e.g. `"position.x"`
+fn set_path<T: Reflect>(component: &mut T, path: &str, value: Value) -> Result<(), ReflectError> {
+    match path.split_once('.') {
+        None => component.set_field(path, value),
+        Some((field, rest)) => {
+            if rest.contains('.') {
+                return Err(ReflectError::UnknownField(path.to_owned()));
+            }
+            let mut current = component
+                .field(field)
+                .ok_or_else(|| ReflectError::UnknownField(field.to_owned()))?;
+            current.set_component(rest, value)?;
+            component.set_field(field, current)
+        }
+    }
+}
+
+/// Type-erased accessors for a registered component type
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentRegistration {
+    name: &'static str,
+    type_id: TypeId,
+    fields: &'static [FieldInfo],
+    contains: fn(&hecs::World, Entity) -> bool,
+    get: fn(&hecs::World, Entity, &str) -> Result<Value, ReflectError>,
+    set: fn(&mut hecs::World, Entity, &str, Value) -> Result<(), ReflectError>,
+    insert_default: fn(&mut hecs::World, Entity) -> Result<(), ReflectError>,
+}
+
+impl ComponentRegistration {
+    /// Create the registration for a reflected component type
+    #[must_use]
+    pub fn of<T: Reflect>() -> Self {
+        Self {
+            name: T::type_name(),
+            type_id: TypeId::of::<T>(),
+            fields: T::fields(),
+            contains: |world, entity| world.satisfies::<&T>(entity).unwrap_or(false),
+            get: |world, entity, path| {
+                let component = world.get::<&T>(entity).map_err(ReflectError::from)?;
+                get_path(&*component, path)
+            },
+            set: |world, entity, path, value| {
+                let mut component = world.get::<&mut T>(entity).map_err(ReflectError::from)?;
+                set_path(&mut *component, path, value)
+            },
+            insert_default: |world, entity| {
+                world
+                    .insert_one(entity, T::default())
+                    .map_err(|_| ReflectError::NoSuchEntity)
+            },
+        }
+    }
+
+    /// Component name
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Component type ID
+    #[must_use]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Reflected fields
+    #[must_use]
+    pub fn fields(&self) -> &'static [FieldInfo] {
+        self.fields
+    }
+
+    /// Check if an entity has this component
+    #[must_use]
+    pub fn contains(&self, world: &hecs::World, entity: Entity) -> bool {
+        (self.contains)(world, entity)
+    }
+
+    /// Read a field by dotted path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity, component or field does not exist
+    pub fn get(
+        &self,
+        world: &hecs::World,
+        entity: Entity,
+        path: &str,
+    ) -> Result<Value, ReflectError> {
+        (self.get)(world, entity, path)
+    }
+
+    /// Write a field by dotted path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity, component or field does not exist or the
+    /// value has the wrong type
+    pub fn set(
+        &self,
+        world: &mut hecs::World,
+        entity: Entity,
+        path: &str,
+        value: Value,
+    ) -> Result<(), ReflectError> {
+        (self.set)(world, entity, path, value)
+    }
+
+    /// Insert a default-valued instance of this component
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity does not exist
+    pub fn insert_default(
+        &self,
+        world: &mut hecs::World,
+        entity: Entity,
+    ) -> Result<(), ReflectError> {
+        (self.insert_default)(world, entity)
+    }
+}
+
+/// Registry of reflected component types
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    /// Registrations in registration order
+    registrations: Vec<ComponentRegistration>,
+    /// Component name to registration index
+    by_name: FxHashMap<&'static str, usize>,
+    /// Type ID to registration index
+    by_type: FxHashMap<TypeId, usize>,
+    /// Clone functions for cloneable component types
+    cloners: FxHashMap<TypeId, CloneFn>,
+}
+
+/// Copies one component from an entity into a builder
+pub type CloneFn = fn(&hecs::World, Entity, &mut hecs::EntityBuilderClone);
+
+impl TypeRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in components registered
+    #[must_use]
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register::<Transform>();
+        registry.register::<Velocity>();
+        registry.register::<Name>();
+
+        registry.register_clone::<Transform>();
+        registry.register_clone::<Velocity>();
+        registry.register_clone::<Name>();
+        registry.register_clone::<Tags>();
+        registry.register_clone::<GlobalTransform>();
+        registry.register_clone::<MotionSettings>();
+        registry.register_clone::<PhysicsDriven>();
+        registry
+    }
+
+    /// Register a component type, replacing any previous registration
+    pub fn register<T: Reflect>(&mut self) {
+        self.add(ComponentRegistration::of::<T>());
+    }
+
+    /// Register a component type as cloneable
+    ///
+    /// Cloneable components are copied by [`World::clone_entity`](super::World::clone_entity)
+    /// and captured into templates. Hierarchy components are handled separately.
+    pub fn register_clone<T: hecs::Component + Clone>(&mut self) {
+        self.cloners
+            .insert(TypeId::of::<T>(), |world, entity, builder| {
+                if let Ok(component) = world.get::<&T>(entity) {
+                    builder.add((*component).clone());
+                }
+            });
+    }
+
+    /// Check if a component type is registered as cloneable
+    #[must_use]
+    pub fn is_cloneable(&self, type_id: TypeId) -> bool {
+        self.cloners.contains_key(&type_id)
+    }
+
+    /// Copy all cloneable components of an entity into a builder
+    pub fn clone_components(
+        &self,
+        world: &hecs::World,
+        entity: Entity,
+        builder: &mut hecs::EntityBuilderClone,
+    ) {
+        for clone in self.cloners.values() {
+            clone(world, entity, builder);
+        }
+    }
+
+    /// Add a prebuilt registration, replacing any previous one for the same type
+    pub fn add(&mut self, registration: ComponentRegistration) {
+        if let Some(&index) = self.by_type.get(&registration.type_id) {
+            self.by_name.remove(self.registrations[index].name);
+            self.by_name.insert(registration.name, index);
+            self.registrations[index] = registration;
+            return;
+        }
+
+        let index = self.registrations.len();
+        self.by_name.insert(registration.name, index);
+        self.by_type.insert(registration.type_id, index);
+        self.registrations.push(registration);
+    }
+
+    /// Look up a registration by component name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ComponentRegistration> {
+        self.by_name.get(name).map(|&i| &self.registrations[i])
+    }
+
+    /// Look up a registration by type
+    #[must_use]
+    pub fn get_by_type(&self, type_id: TypeId) -> Option<&ComponentRegistration> {
+        self.by_type.get(&type_id).map(|&i| &self.registrations[i])
+    }
+
+    /// Iterate over all registrations
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentRegistration> {
+        self.registrations.iter()
+    }
+
+    /// Get the number of registered types
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    /// Check if no types are registered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+}
+
+/// A component serialized through reflection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReflectedComponent {
+    /// Registered component name
+    pub component: String,
+    /// Field values by name
+    pub fields: Vec<(String, Value)>,
+}
+
+/// Errors that can occur during reflection
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReflectError {
+    /// The entity does not exist
+    NoSuchEntity,
+    /// The component type is not registered
+    UnknownComponent(String),
+    /// The entity does not have the component
+    MissingComponent(String),
+    /// The field or path does not exist
+    UnknownField(String),
+    /// The value has the wrong type for the field
+    TypeMismatch {
+        expected: ValueKind,
+        found: ValueKind,
+    },
+}
+
+impl From<hecs::ComponentError> for ReflectError {
+    fn from(error: hecs::ComponentError) -> Self {
+        match error {
+            hecs::ComponentError::NoSuchEntity => Self::NoSuchEntity,
+            hecs::ComponentError::MissingComponent(missing) => {
+                Self::MissingComponent(missing.to_string())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchEntity => write!(f, "No such entity"),
+            Self::UnknownComponent(c) => write!(f, "Unknown component: {c}"),
+            Self::MissingComponent(c) => write!(f, "Missing component: {c}"),
+            Self::UnknownField(p) => write!(f, "Unknown field: {p}"),
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "Type mismatch: expected {expected:?}, found {found:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_paths() {
+        let mut transform = Transform::from_position(Vec3::new(1.0, 2.0, 3.0));
+
+        assert_eq!(get_path(&transform, "position.y"), Ok(Value::F32(2.0)));
+        assert_eq!(get_path(&transform, "scale"), Ok(Value::Vec3(Vec3::ONE)));
+
+        set_path(&mut transform, "position.x", Value::F32(5.0)).unwrap();
+        assert_eq!(transform.position.x, 5.0);
+
+        assert_eq!(
+            set_path(&mut transform, "scale", Value::F32(1.0)),
+            Err(ReflectError::TypeMismatch {
+                expected: ValueKind::Vec3,
+                found: ValueKind::F32,
+            })
+        );
+        assert!(get_path(&transform, "position.q").is_err());
+
+        // Writing a quaternion component by component keeps earlier writes
+        let target = Quat::from_rotation_y(1.0);
+        for (name, value) in ["x", "y", "z", "w"].iter().zip(target.to_array()) {
+            set_path(
+                &mut transform,
+                &format!("rotation.{name}"),
+                Value::F32(value),
+            )
+            .unwrap();
+        }
+        assert_eq!(transform.rotation, target);
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry = TypeRegistry::with_builtins();
+        let registration = registry.get("Velocity").unwrap();
+
+        assert_eq!(registration.type_id(), TypeId::of::<Velocity>());
+        assert_eq!(registration.fields().len(), 2);
+        assert!(registry.get("Unknown").is_none());
+    }
+}
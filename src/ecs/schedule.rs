@@ -0,0 +1,473 @@
+//! System scheduling with declared component access
+//!
+//! Systems declare which components they read and write. Systems that don't
+//! conflict are grouped into stages and run concurrently on a worker pool
+//! that is kept for the lifetime of the schedule; conflicting systems always
+//! run in registration order.
+
+use std::any::TypeId;
+use std::fmt::Write;
+
+use hecs::Entity;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use super::world::World;
+
+/// A deferred world mutation
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A boxed system function
+type SystemFn = Box<dyn FnMut(&World, &mut Commands, f32) + Send>;
+
+/// Queue of deferred world mutations
+///
+/// Systems only get shared access to the world, so structural changes such as
+/// spawning and despawning are recorded here and applied after the stage.
+#[derive(Default)]
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Commands {
+    /// Create an empty command queue
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an arbitrary world mutation
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    /// Queue spawning an entity
+    pub fn spawn(&mut self, components: impl hecs::DynamicBundle + Send + 'static) {
+        self.push(move |world| {
+            world.spawn(components);
+        });
+    }
+
+    /// Queue despawning an entity
+    pub fn despawn(&mut self, entity: Entity) {
+        self.push(move |world| {
+            let _ = world.despawn(entity);
+        });
+    }
+
+    /// Queue inserting components on an entity
+    pub fn insert(
+        &mut self,
+        entity: Entity,
+        components: impl hecs::DynamicBundle + Send + 'static,
+    ) {
+        self.push(move |world| {
+            let _ = world.insert(entity, components);
+        });
+    }
+
+    /// Queue removing a component from an entity
+    pub fn remove_one<T: hecs::Component>(&mut self, entity: Entity) {
+        self.push(move |world| {
+            let _ = world.remove_one::<T>(entity);
+        });
+    }
+
+    /// Apply all queued commands in order
+    pub fn apply(&mut self, world: &mut World) {
+        for command in self.queue.drain(..) {
+            command(world);
+        }
+    }
+
+    /// Get the number of queued commands
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Check if no commands are queued
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("len", &self.queue.len())
+            .finish()
+    }
+}
+
+/// Component types a system reads and writes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemAccess {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+}
+
+impl SystemAccess {
+    /// Declare read access to a component type
+    pub fn add_read<T: hecs::Component>(&mut self) {
+        let entry = (TypeId::of::<T>(), std::any::type_name::<T>());
+        if !self.reads.contains(&entry) {
+            self.reads.push(entry);
+        }
+    }
+
+    /// Declare write access to a component type
+    pub fn add_write<T: hecs::Component>(&mut self) {
+        let entry = (TypeId::of::<T>(), std::any::type_name::<T>());
+        if !self.writes.contains(&entry) {
+            self.writes.push(entry);
+        }
+    }
+
+    /// Get the component types that conflict between two access sets
+    #[must_use]
+    pub fn conflicts(&self, other: &Self) -> Vec<&'static str> {
+        let mut conflicts = Vec::new();
+        for &(id, name) in &self.writes {
+            if other
+                .reads
+                .iter()
+                .chain(&other.writes)
+                .any(|&(o, _)| o == id)
+            {
+                conflicts.push(name);
+            }
+        }
+        for &(id, name) in &self.reads {
+            if other.writes.iter().any(|&(o, _)| o == id) && !conflicts.contains(&name) {
+                conflicts.push(name);
+            }
+        }
+        conflicts
+    }
+
+    /// Check if two access sets conflict
+    #[must_use]
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        !self.conflicts(other).is_empty()
+    }
+}
+
+/// A system function with its declared component access
+pub struct System {
+    name: String,
+    access: SystemAccess,
+    run: SystemFn,
+    commands: Commands,
+    stage: usize,
+}
+
+impl System {
+    /// Create a new system
+    ///
+    /// The function receives the world, a command queue for structural changes,
+    /// and the frame delta time in seconds. Every component the function
+    /// queries must be declared with [`System::reads`] or [`System::writes`].
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        run: impl FnMut(&World, &mut Commands, f32) + Send + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            access: SystemAccess::default(),
+            run: Box::new(run),
+            commands: Commands::new(),
+            stage: 0,
+        }
+    }
+
+    /// Declare read access to a component type
+    #[must_use]
+    pub fn reads<T: hecs::Component>(mut self) -> Self {
+        self.access.add_read::<T>();
+        self
+    }
+
+    /// Declare write access to a component type
+    #[must_use]
+    pub fn writes<T: hecs::Component>(mut self) -> Self {
+        self.access.add_write::<T>();
+        self
+    }
+
+    /// Get the system name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the declared component access
+    #[must_use]
+    pub fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    fn execute(&mut self, world: &World, dt: f32) {
+        (self.run)(world, &mut self.commands, dt);
+    }
+}
+
+impl std::fmt::Debug for System {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("System")
+            .field("name", &self.name)
+            .field("access", &self.access)
+            .field("stage", &self.stage)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Ordered collection of systems executed in parallel stages
+#[derive(Debug)]
+pub struct Schedule {
+    systems: Vec<System>,
+    /// Number of stages in the execution graph
+    stage_count: usize,
+    /// Run non-conflicting systems on worker threads
+    parallel: bool,
+    /// Worker threads, created on the first parallel stage and reused
+    pool: Option<ThreadPool>,
+}
+
+impl Schedule {
+    /// Create an empty schedule
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            stage_count: 0,
+            parallel: true,
+            pool: None,
+        }
+    }
+
+    /// Add a system after all previously added systems
+    pub fn add_system(&mut self, mut system: System) -> &mut Self {
+        // Run after the latest earlier system it conflicts with
+        system.stage = self
+            .systems
+            .iter()
+            .filter(|other| system.access.conflicts_with(&other.access))
+            .map(|other| other.stage + 1)
+            .max()
+            .unwrap_or(0);
+        self.stage_count = self.stage_count.max(system.stage + 1);
+        self.systems.push(system);
+        self
+    }
+
+    /// Remove a system by name, returns true if it was found
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        let Some(index) = self.systems.iter().position(|s| s.name == name) else {
+            return false;
+        };
+        let systems = std::mem::take(&mut self.systems);
+        self.stage_count = 0;
+        for (i, system) in systems.into_iter().enumerate() {
+            if i != index {
+                self.add_system(system);
+            }
+        }
+        true
+    }
+
+    /// Enable or disable running systems on worker threads
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// Check if parallel execution is enabled
+    #[must_use]
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Get the number of systems
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Check if the schedule has no systems
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Get system names grouped by stage
+    #[must_use]
+    pub fn stages(&self) -> Vec<Vec<&str>> {
+        let mut stages = vec![Vec::new(); self.stage_count];
+        for system in &self.systems {
+            stages[system.stage].push(system.name.as_str());
+        }
+        stages
+    }
+
+    /// Run all systems once
+    ///
+    /// Commands recorded by a stage are applied in system order before the next
+    /// stage starts.
+    pub fn run(&mut self, world: &mut World, dt: f32) {
+        for stage in 0..self.stage_count {
+            let mut batch: Vec<&mut System> = self
+                .systems
+                .iter_mut()
+                .filter(|system| system.stage == stage)
+                .collect();
+
+            let pool = if self.parallel && batch.len() > 1 {
+                Self::worker_pool(&mut self.pool)
+            } else {
+                None
+            };
+            if let Some(pool) = pool {
+                let shared: &World = world;
+                pool.scope(|scope| {
+                    let (first, rest) = batch.split_first_mut().expect("batch is not empty");
+                    for system in rest.iter_mut() {
+                        scope.spawn(move |_| system.execute(shared, dt));
+                    }
+                    first.execute(shared, dt);
+                });
+            } else {
+                for system in &mut batch {
+                    system.execute(world, dt);
+                }
+            }
+
+            for system in batch {
+                system.commands.apply(world);
+            }
+        }
+    }
+
+    /// Get the worker pool, creating it on first use
+    ///
+    /// Falls back to running on the calling thread if no threads can be spawned.
+    fn worker_pool(pool: &mut Option<ThreadPool>) -> Option<&ThreadPool> {
+        if pool.is_none() {
+            match ThreadPoolBuilder::new()
+                .thread_name(|i| format!("schedule-worker-{i}"))
+                .build()
+            {
+                Ok(built) => *pool = Some(built),
+                Err(e) => log::warn!("Failed to create system worker pool: {e}"),
+            }
+        }
+        pool.as_ref()
+    }
+
+    /// Describe the execution graph for debugging
+    #[must_use]
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for (stage, names) in self.stages().iter().enumerate() {
+            let _ = writeln!(report, "Stage {stage}: {}", names.join(", "));
+        }
+
+        for (i, system) in self.systems.iter().enumerate() {
+            for earlier in &self.systems[..i] {
+                let conflicts = system.access.conflicts(&earlier.access);
+                if !conflicts.is_empty() {
+                    let _ = writeln!(
+                        report,
+                        "  {} after {} ({})",
+                        system.name,
+                        earlier.name,
+                        conflicts.join(", ")
+                    );
+                }
+            }
+        }
+        report
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Name, Transform, Velocity};
+
+    #[test]
+    fn test_stage_assignment() {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(System::new("a", |_, _, _| {}).writes::<Transform>())
+            .add_system(System::new("b", |_, _, _| {}).reads::<Velocity>())
+            .add_system(System::new("c", |_, _, _| {}).reads::<Transform>())
+            .add_system(System::new("d", |_, _, _| {}).writes::<Velocity>());
+
+        assert_eq!(schedule.stages(), vec![vec!["a", "b"], vec!["c", "d"]]);
+        assert!(schedule.report().contains("c after a"));
+    }
+
+    #[test]
+    fn test_parallel_run_and_commands() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::default(), Velocity::default()));
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(
+                System::new("move", |world, _, dt| {
+                    for (_, transform) in world.query::<&mut Transform>().iter() {
+                        transform.position.x += dt;
+                    }
+                })
+                .writes::<Transform>(),
+            )
+            .add_system(
+                System::new("spawner", |world, commands, _| {
+                    if world.find_by_name("Spawned").is_none() {
+                        commands.spawn((Name::new("Spawned"),));
+                    }
+                })
+                .reads::<Name>(),
+            );
+
+        schedule.run(&mut world, 1.0);
+        schedule.run(&mut world, 1.0);
+
+        assert_eq!(world.get::<Transform>(entity).unwrap().position.x, 2.0);
+        assert_eq!(world.find_all_by_name("Spawned").count(), 1);
+    }
+
+    #[test]
+    fn test_worker_pool_is_reused() {
+        use std::sync::{Arc, Mutex};
+
+        let threads = Arc::new(Mutex::new(Vec::new()));
+        let mut schedule = Schedule::new();
+        for name in ["a", "b", "c"] {
+            let threads = Arc::clone(&threads);
+            schedule.add_system(System::new(name, move |_, _, _| {
+                let thread = std::thread::current();
+                threads.lock().unwrap().push(thread.id());
+            }));
+        }
+
+        let mut world = World::new();
+        for _ in 0..20 {
+            schedule.run(&mut world, 1.0);
+        }
+
+        let pool = schedule.pool.as_ref().expect("pool was created");
+        let mut ids = threads.lock().unwrap().clone();
+        ids.sort_unstable_by_key(|id| format!("{id:?}"));
+        ids.dedup();
+        assert!(ids.len() <= pool.current_num_threads() + 1);
+    }
+}
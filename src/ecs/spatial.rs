@@ -0,0 +1,525 @@
+//! Spatial index over entity transforms
+//!
+//! A uniform hash grid for "entities near a point" style queries.
+
+use glam::{IVec3, Vec3};
+use hecs::{Entity, Or};
+use rustc_hash::{FxHashMap, FxHashSet};
+use smallvec::SmallVec;
+
+use super::components::Transform;
+use super::hierarchy::GlobalTransform;
+use super::pool::Disabled;
+use super::world::World;
+
+/// Grid entry for a single entity
+#[derive(Debug, Clone, Copy)]
+struct SpatialEntry {
+    /// Last known world position
+    position: Vec3,
+    /// Cell the entity is stored in
+    cell: IVec3,
+}
+
+/// Uniform hash grid over entity positions
+///
+/// Positions are read from [`GlobalTransform`] when present, otherwise from
+/// [`Transform`]. Call [`SpatialGrid::update`] once per frame; entities that
+/// didn't move since the last update are skipped, and only entities that
+/// moved to a different cell are re-bucketed.
+#[derive(Debug)]
+pub struct SpatialGrid {
+    /// Edge length of a grid cell
+    cell_size: f32,
+    /// Entities stored per occupied cell
+    cells: FxHashMap<IVec3, SmallVec<[Entity; 8]>>,
+    /// Per-entity bookkeeping
+    entries: FxHashMap<Entity, SpatialEntry>,
+    /// Min and max occupied cell, possibly larger than needed after removals
+    bounds: Option<(IVec3, IVec3)>,
+    /// Whether a cell was emptied since the bounds were last recomputed
+    bounds_stale: bool,
+}
+
+impl SpatialGrid {
+    /// Create a new grid with the given cell size
+    ///
+    /// A good cell size is roughly the typical query radius.
+    #[must_use]
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: FxHashMap::default(),
+            entries: FxHashMap::default(),
+            bounds: None,
+            bounds_stale: false,
+        }
+    }
+
+    /// Get the cell size
+    #[must_use]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Get the number of indexed entities
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the grid is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all entities from the grid
+    pub fn clear(&mut self) {
+        self.bounds = None;
+        self.bounds_stale = false;
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Synchronize the grid with entity transforms in the world
+    ///
+    /// Entities whose position matches the last update are skipped. Entities
+    /// that no longer exist, lost their transform or were [`Disabled`] are
+    /// removed.
+    pub fn update(&mut self, world: &World) {
+        let mut seen = 0;
+        for (entity, transform) in world
+            .query::<Or<&GlobalTransform, &Transform>>()
+            .without::<&Disabled>()
+            .iter()
+        {
+            seen += 1;
+            let position = match transform {
+                Or::Left(global) | Or::Both(global, _) => global.position(),
+                Or::Right(local) => local.position,
+            };
+            if self
+                .entries
+                .get(&entity)
+                .is_none_or(|entry| entry.position != position)
+            {
+                self.insert(entity, position);
+            }
+        }
+
+        // Every indexed entity was seen, so none of them are stale
+        if seen != self.entries.len() {
+            self.remove_stale(world);
+        }
+        if self.bounds_stale {
+            self.recompute_bounds();
+        }
+    }
+
+    fn remove_stale(&mut self, world: &World) {
+        let stale: Vec<Entity> = self
+            .entries
+            .keys()
+            .copied()
+            .filter(|&entity| {
+                world.get::<Disabled>(entity).is_ok()
+                    || !world
+                        .inner
+                        .satisfies::<Or<&GlobalTransform, &Transform>>(entity)
+                        .unwrap_or(false)
+            })
+            .collect();
+        for entity in stale {
+            self.remove(entity);
+        }
+    }
+
+    /// Insert or move an entity
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell_of(position);
+
+        if let Some(entry) = self.entries.get_mut(&entity) {
+            entry.position = position;
+            if entry.cell == cell {
+                return;
+            }
+            let old_cell = entry.cell;
+            entry.cell = cell;
+            self.bounds_stale |= Self::remove_from_cell(&mut self.cells, old_cell, entity);
+        } else {
+            self.entries.insert(entity, SpatialEntry { position, cell });
+        }
+
+        self.cells.entry(cell).or_default().push(entity);
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min(cell), max.max(cell)),
+            None => (cell, cell),
+        });
+    }
+
+    /// Remove an entity, returns true if it was indexed
+    pub fn remove(&mut self, entity: Entity) -> bool {
+        if let Some(entry) = self.entries.remove(&entity) {
+            self.bounds_stale |= Self::remove_from_cell(&mut self.cells, entry.cell, entity);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the indexed position of an entity
+    #[must_use]
+    pub fn position(&self, entity: Entity) -> Option<Vec3> {
+        self.entries.get(&entity).map(|entry| entry.position)
+    }
+
+    /// Find all entities within `radius` of `center`
+    #[must_use]
+    pub fn query_radius(&self, center: Vec3, radius: f32) -> Vec<Entity> {
+        let radius_sq = radius * radius;
+        let mut result = Vec::new();
+        self.visit_aabb(
+            center - Vec3::splat(radius),
+            center + Vec3::splat(radius),
+            |entity, position| {
+                if position.distance_squared(center) <= radius_sq {
+                    result.push(entity);
+                }
+            },
+        );
+        result
+    }
+
+    /// Find all entities inside an axis-aligned box
+    #[must_use]
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<Entity> {
+        let mut result = Vec::new();
+        self.visit_aabb(min, max, |entity, position| {
+            if position.cmpge(min).all() && position.cmple(max).all() {
+                result.push(entity);
+            }
+        });
+        result
+    }
+
+    /// Find up to `k` entities nearest to `point`, sorted by distance
+    ///
+    /// Entities further than `max_distance` are ignored.
+    #[must_use]
+    pub fn k_nearest(&self, point: Vec3, k: usize, max_distance: f32) -> Vec<(Entity, f32)> {
+        if k == 0 || self.entries.is_empty() {
+            return Vec::new();
+        }
+
+        // Grow the search radius until enough candidates are found
+        let mut radius = self.cell_size.min(max_distance);
+        let mut candidates = loop {
+            let found = self.query_radius(point, radius);
+            if found.len() >= k || radius >= max_distance || found.len() == self.entries.len() {
+                break found;
+            }
+            radius = (radius * 2.0).min(max_distance);
+        };
+
+        let mut result: Vec<(Entity, f32)> = candidates
+            .drain(..)
+            .filter_map(|entity| {
+                self.position(entity)
+                    .map(|position| (entity, position.distance(point)))
+            })
+            .collect();
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+        result.truncate(k);
+        result
+    }
+
+    /// Find entities whose bounding sphere of `radius` is hit by a ray
+    ///
+    /// Returns hits sorted by distance along the ray.
+    #[must_use]
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        radius: f32,
+    ) -> Vec<(Entity, f32)> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO || max_distance <= 0.0 {
+            return Vec::new();
+        }
+
+        // Only walk the part of the ray inside the occupied cells
+        let Some((min_cell, max_cell)) = self.bounds else {
+            return Vec::new();
+        };
+        let radius = radius.max(0.0);
+        let pad = Vec3::splat(radius);
+        let min = min_cell.as_vec3() * self.cell_size - pad;
+        let max = (max_cell + IVec3::ONE).as_vec3() * self.cell_size + pad;
+        let Some((enter, exit)) = ray_aabb(origin, direction, min, max) else {
+            return Vec::new();
+        };
+        let mut t = enter.max(0.0);
+        let end = max_distance.min(exit);
+
+        // 3D-DDA: visit the cells the ray passes through in order, padding
+        // each by the radius so spheres centered in neighbouring cells count
+        let mut cell = self.cell_of(origin + direction * t);
+        let step = direction.signum().as_ivec3();
+        let t_delta = (Vec3::splat(self.cell_size) / direction.abs()).to_array();
+        let mut t_max = [0.0; 3];
+        for axis in 0..3 {
+            t_max[axis] = if direction[axis] == 0.0 {
+                f32::INFINITY
+            } else {
+                let boundary = (cell[axis] + i32::from(step[axis] > 0)) as f32 * self.cell_size;
+                (boundary - origin[axis]) / direction[axis]
+            };
+        }
+
+        let mut visited = FxHashSet::default();
+        let mut hits = Vec::new();
+        while t <= end {
+            let cell_min = cell.as_vec3() * self.cell_size;
+            let cell_max = cell_min + Vec3::splat(self.cell_size);
+            self.visit_aabb(cell_min - pad, cell_max + pad, |entity, position| {
+                if visited.insert(entity)
+                    && let Some(distance) = ray_sphere(origin, direction, position, radius)
+                    && distance <= max_distance
+                {
+                    hits.push((entity, distance));
+                }
+            });
+
+            let axis = if t_max[0] < t_max[1] {
+                if t_max[0] < t_max[2] { 0 } else { 2 }
+            } else if t_max[1] < t_max[2] {
+                1
+            } else {
+                2
+            };
+            if !t_max[axis].is_finite() {
+                break;
+            }
+            t = t_max[axis];
+            t_max[axis] += t_delta[axis];
+            cell[axis] = cell[axis].saturating_add(step[axis]);
+        }
+
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+
+    /// Recompute the occupied cell bounds after cells were emptied
+    fn recompute_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, &cell| {
+            Some(match bounds {
+                Some((min, max)) => (IVec3::min(min, cell), IVec3::max(max, cell)),
+                None => (cell, cell),
+            })
+        });
+        self.bounds_stale = false;
+    }
+
+    fn cell_of(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Remove an entity from a cell, returns true if the cell became empty
+    fn remove_from_cell(
+        cells: &mut FxHashMap<IVec3, SmallVec<[Entity; 8]>>,
+        cell: IVec3,
+        entity: Entity,
+    ) -> bool {
+        let Some(entities) = cells.get_mut(&cell) else {
+            return false;
+        };
+        entities.retain(|e| *e != entity);
+        if entities.is_empty() {
+            cells.remove(&cell);
+            return true;
+        }
+        false
+    }
+
+    /// Visit every entity in cells overlapping the box
+    fn visit_aabb(&self, min: Vec3, max: Vec3, mut visit: impl FnMut(Entity, Vec3)) {
+        let Some((occupied_min, occupied_max)) = self.bounds else {
+            return;
+        };
+        let min_cell = self.cell_of(min).max(occupied_min);
+        let max_cell = self.cell_of(max).min(occupied_max);
+        if min_cell.cmpgt(max_cell).any() {
+            return;
+        }
+        let extent = max_cell.as_i64vec3() - min_cell.as_i64vec3() + 1;
+        let range_cells = extent.x.saturating_mul(extent.y).saturating_mul(extent.z);
+
+        let mut visit_cell = |entities: &SmallVec<[Entity; 8]>| {
+            for &entity in entities {
+                if let Some(entry) = self.entries.get(&entity) {
+                    visit(entity, entry.position);
+                }
+            }
+        };
+
+        // Large boxes are cheaper to answer by scanning occupied cells
+        if range_cells > self.cells.len() as i64 {
+            for (cell, entities) in &self.cells {
+                if cell.cmpge(min_cell).all() && cell.cmple(max_cell).all() {
+                    visit_cell(entities);
+                }
+            }
+            return;
+        }
+
+        for z in min_cell.z..=max_cell.z {
+            for y in min_cell.y..=max_cell.y {
+                for x in min_cell.x..=max_cell.x {
+                    if let Some(entities) = self.cells.get(&IVec3::new(x, y, z)) {
+                        visit_cell(entities);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(4.0)
+    }
+}
+
+/// Distances along a ray where it enters and leaves a box, if it hits it
+fn ray_aabb(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<(f32, f32)> {
+    let inverse = direction.recip();
+    let a = (min - origin) * inverse;
+    let b = (max - origin) * inverse;
+    // NaN comes from a zero direction component with the origin on a slab edge
+    let near = a.min(b).to_array().into_iter().filter(|t| !t.is_nan());
+    let far = a.max(b).to_array().into_iter().filter(|t| !t.is_nan());
+    let enter = near.fold(f32::NEG_INFINITY, f32::max);
+    let exit = far.fold(f32::INFINITY, f32::min);
+    (enter <= exit && exit >= 0.0).then_some((enter, exit))
+}
+
+/// Distance along a normalized ray to a sphere, if hit
+fn ray_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = center - origin;
+    let t = to_center.dot(direction);
+    let closest_sq = to_center.length_squared() - t * t;
+    let radius_sq = radius * radius;
+
+    if closest_sq > radius_sq {
+        return None;
+    }
+
+    let half_chord = (radius_sq - closest_sq).sqrt();
+    let distance = t - half_chord;
+    if distance >= 0.0 {
+        Some(distance)
+    } else if t + half_chord >= 0.0 {
+        // Origin is inside the sphere
+        Some(0.0)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radius_and_aabb_queries() {
+        let mut world = World::new();
+        let near = world.spawn((Transform::from_position(Vec3::new(1.0, 0.0, 0.0)),));
+        let far = world.spawn((Transform::from_position(Vec3::new(20.0, 0.0, 0.0)),));
+
+        let mut grid = SpatialGrid::new(2.0);
+        grid.update(&world);
+
+        assert_eq!(grid.query_radius(Vec3::ZERO, 5.0), vec![near]);
+        assert_eq!(
+            grid.query_aabb(Vec3::new(15.0, -1.0, -1.0), Vec3::new(25.0, 1.0, 1.0)),
+            vec![far]
+        );
+
+        // Huge boxes saturate cell coordinates without overflowing
+        let mut all = grid.query_aabb(Vec3::splat(-1e30), Vec3::splat(1e30));
+        all.sort_unstable_by_key(|e| e.to_bits());
+        assert_eq!(all, vec![near, far]);
+        assert_eq!(grid.query_radius(Vec3::ZERO, 1e30).len(), 2);
+
+        // Bounds shrink once the far cell is emptied
+        world.despawn(far).unwrap();
+        grid.update(&world);
+        assert_eq!(grid.bounds, Some((IVec3::ZERO, IVec3::ZERO)));
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::from_position(Vec3::ZERO),));
+
+        let mut grid = SpatialGrid::new(2.0);
+        grid.update(&world);
+
+        world.get_mut::<Transform>(entity).unwrap().position = Vec3::new(10.0, 0.0, 0.0);
+        grid.update(&world);
+        assert!(grid.query_radius(Vec3::ZERO, 1.0).is_empty());
+        assert_eq!(
+            grid.query_radius(Vec3::new(10.0, 0.0, 0.0), 1.0),
+            vec![entity]
+        );
+
+        // Unmoved entities keep their entry; disabled ones are dropped
+        let other = world.spawn((Transform::from_position(Vec3::ONE),));
+        grid.update(&world);
+        assert_eq!(grid.len(), 2);
+        world.insert_one(other, Disabled).unwrap();
+        grid.update(&world);
+        assert_eq!(grid.position(other), None);
+
+        world.despawn(entity).unwrap();
+        grid.update(&world);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_k_nearest_and_raycast() {
+        let mut world = World::new();
+        let a = world.spawn((Transform::from_position(Vec3::new(1.0, 0.0, 0.0)),));
+        let b = world.spawn((Transform::from_position(Vec3::new(3.0, 0.0, 0.0)),));
+        let c = world.spawn((Transform::from_position(Vec3::new(30.0, 0.0, 0.0)),));
+
+        let mut grid = SpatialGrid::new(1.0);
+        grid.update(&world);
+
+        let nearest = grid.k_nearest(Vec3::ZERO, 2, f32::INFINITY);
+        assert_eq!(
+            nearest.iter().map(|(e, _)| *e).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+
+        let hits = grid.raycast(Vec3::ZERO, Vec3::X, f32::INFINITY, 0.5);
+        assert_eq!(
+            hits.iter().map(|(e, _)| *e).collect::<Vec<_>>(),
+            vec![a, b, c]
+        );
+        assert!((hits[0].1 - 0.5).abs() < 0.001);
+
+        // The walk stays inside the occupied cells, whatever the distance
+        world.spawn((Transform::from_position(Vec3::new(0.0, 1e6, 0.0)),));
+        grid.update(&world);
+        let hits = grid.raycast(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, f32::INFINITY, 0.5);
+        assert_eq!(hits.len(), 3);
+        assert!(
+            grid.raycast(Vec3::ZERO, Vec3::NEG_X, f32::INFINITY, 0.5)
+                .is_empty()
+        );
+    }
+}
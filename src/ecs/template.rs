@@ -0,0 +1,229 @@
+//! Entity cloning and runtime templates
+//!
+//! Only components registered with
+//! [`TypeRegistry::register_clone`](super::TypeRegistry::register_clone) are
+//! copied. [`Parent`] and [`Children`] are remapped so that cloned hierarchies
+//! reference the new entities.
+
+use std::sync::Arc;
+
+use hecs::{BuiltEntityClone, Entity, EntityBuilderClone};
+
+use super::hierarchy::{Children, Parent};
+use super::world::World;
+
+/// A reusable entity prototype with optional children
+#[derive(Clone)]
+pub struct Template {
+    /// Components of the root entity
+    components: BuiltEntityClone,
+    /// Child templates, spawned as children of the root
+    children: Vec<Template>,
+}
+
+impl Template {
+    /// Create a template from a builder
+    #[must_use]
+    pub fn new(builder: EntityBuilderClone) -> Self {
+        Self {
+            components: builder.build(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Add a child template
+    #[must_use]
+    pub fn with_child(mut self, child: Template) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Get the child templates
+    #[must_use]
+    pub fn children(&self) -> &[Template] {
+        &self.children
+    }
+
+    /// Get the root components
+    #[must_use]
+    pub fn components(&self) -> &BuiltEntityClone {
+        &self.components
+    }
+}
+
+impl std::fmt::Debug for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Template")
+            .field("children", &self.children.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl World {
+    /// Duplicate an entity without its children
+    ///
+    /// The copy is attached to the same parent as the original.
+    /// Returns `None` if the entity does not exist.
+    pub fn clone_entity(&mut self, entity: Entity) -> Option<Entity> {
+        let template = Template::new(self.capture_components(entity)?);
+        let parent = self.get::<Parent>(entity).ok().map(|p| p.entity());
+        Some(self.spawn_template_under(&template, parent))
+    }
+
+    /// Duplicate an entity together with all of its descendants
+    ///
+    /// The copy is attached to the same parent as the original.
+    /// Returns `None` if the entity does not exist.
+    pub fn clone_recursive(&mut self, entity: Entity) -> Option<Entity> {
+        let template = self.capture_template(entity)?;
+        let parent = self.get::<Parent>(entity).ok().map(|p| p.entity());
+        Some(self.spawn_template_under(&template, parent))
+    }
+
+    /// Capture an entity and its descendants as a template
+    pub fn capture_template(&self, entity: Entity) -> Option<Template> {
+        let mut template = Template::new(self.capture_components(entity)?);
+
+        if let Ok(children) = self.get::<Children>(entity) {
+            template.children = children
+                .iter()
+                .filter_map(|&child| self.capture_template(child))
+                .collect();
+        }
+
+        Some(template)
+    }
+
+    /// Register a named template, replacing any existing one with the same ID
+    pub fn register_template(&mut self, id: impl Into<String>, template: Template) {
+        self.templates.insert(id.into(), Arc::new(template));
+    }
+
+    /// Capture an entity as a named template
+    ///
+    /// Returns false if the entity does not exist.
+    pub fn register_template_from(&mut self, id: impl Into<String>, entity: Entity) -> bool {
+        match self.capture_template(entity) {
+            Some(template) => {
+                self.register_template(id, template);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a named template
+    pub fn remove_template(&mut self, id: &str) -> Option<Template> {
+        self.templates.remove(id).map(Arc::unwrap_or_clone)
+    }
+
+    /// Check if a named template exists
+    pub fn has_template(&self, id: &str) -> bool {
+        self.templates.contains_key(id)
+    }
+
+    /// Spawn a named template as a root entity
+    ///
+    /// Returns `None` if no template with that ID is registered.
+    pub fn spawn_template(&mut self, id: &str) -> Option<Entity> {
+        let template = Arc::clone(self.templates.get(id)?);
+        Some(self.spawn_template_under(&template, None))
+    }
+
+    /// Spawn a template, optionally as a child of `parent`
+    pub fn spawn_template_under(&mut self, template: &Template, parent: Option<Entity>) -> Entity {
+        let root = self.spawn(&template.components);
+
+        if let Some(parent) = parent.filter(|&p| self.contains(p)) {
+            self.attach_child(parent, root);
+        }
+
+        for child in &template.children {
+            self.spawn_template_under(child, Some(root));
+        }
+
+        root
+    }
+
+    fn capture_components(&self, entity: Entity) -> Option<EntityBuilderClone> {
+        if !self.contains(entity) {
+            return None;
+        }
+        let mut builder = EntityBuilderClone::new();
+        self.registry()
+            .clone_components(&self.inner, entity, &mut builder);
+        Some(builder)
+    }
+
+    fn attach_child(&mut self, parent: Entity, child: Entity) {
+        let _ = self.insert_one(child, Parent::new(parent));
+
+        if let Ok(mut children) = self.get_mut::<Children>(parent) {
+            children.add(child);
+            return;
+        }
+        let _ = self.insert_one(parent, Children::single(child));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Name, Transform};
+    use glam::Vec3;
+
+    /// Spawn `Root -> Child` and return both entities
+    fn spawn_pair(world: &mut World) -> (Entity, Entity) {
+        let root = world.spawn((Name::new("Root"), Transform::default()));
+        let child = world.spawn((
+            Name::new("Child"),
+            Transform::from_position(Vec3::X),
+            Parent::new(root),
+        ));
+        world.insert_one(root, Children::single(child)).unwrap();
+        (root, child)
+    }
+
+    #[test]
+    fn test_clone_entity_keeps_parent() {
+        let mut world = World::new();
+        let (root, child) = spawn_pair(&mut world);
+
+        let copy = world.clone_entity(child).unwrap();
+
+        assert_eq!(world.get::<Parent>(copy).unwrap().entity(), root);
+        assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+        assert_eq!(world.get::<Transform>(copy).unwrap().position, Vec3::X);
+    }
+
+    #[test]
+    fn test_clone_recursive_remaps_hierarchy() {
+        let mut world = World::new();
+        let (root, child) = spawn_pair(&mut world);
+
+        let copy = world.clone_recursive(root).unwrap();
+
+        let copy_children = world.get::<Children>(copy).unwrap().clone();
+        assert_eq!(copy_children.len(), 1);
+        let copy_child = copy_children.0[0];
+        assert_ne!(copy_child, child);
+        assert_eq!(world.get::<Parent>(copy_child).unwrap().entity(), copy);
+        assert_eq!(world.find_all_by_name("Child").count(), 2);
+    }
+
+    #[test]
+    fn test_named_template() {
+        let mut world = World::new();
+        let (root, _) = spawn_pair(&mut world);
+        assert!(world.register_template_from("pair", root));
+        world.despawn(root).unwrap();
+
+        let a = world.spawn_template("pair").unwrap();
+        let b = world.spawn_template("pair").unwrap();
+
+        assert_ne!(a, b);
+        assert!(world.get::<Parent>(a).is_err());
+        assert!(world.find_by_path("Root/Child").is_some());
+        assert!(world.spawn_template("missing").is_none());
+    }
+}
@@ -1,11 +1,39 @@
 //! World wrapper around hecs
 
+use std::any::TypeId;
+use std::sync::Arc;
+
 use hecs::Entity;
+use rustc_hash::{FxHashMap, FxHashSet};
+use smallvec::SmallVec;
+
+use super::components::{Name, Tags};
+use super::hierarchy::Parent;
+use super::hooks::{HookKind, Hooks};
+use super::reflect::{
+    ComponentRegistration, Reflect, ReflectError, ReflectedComponent, TypeRegistry, Value,
+};
+use super::schedule::Commands;
+use super::template::Template;
 
 /// Game world containing all entities and components
+///
+/// Spawn, despawn and modify entities through the `World` methods rather than
+/// `inner` so that the name and tag indexes stay up to date and lifecycle
+/// hooks fire. [`World::clear`] does not run hooks.
 pub struct World {
     /// The underlying hecs world
     pub inner: hecs::World,
+    /// Entities indexed by name
+    names: FxHashMap<String, SmallVec<[Entity; 1]>>,
+    /// Entities indexed by tag
+    tags: FxHashMap<String, FxHashSet<Entity>>,
+    /// Reflected component types
+    registry: TypeRegistry,
+    /// Named entity templates
+    pub(super) templates: FxHashMap<String, Arc<Template>>,
+    /// Component lifecycle hooks
+    pub(super) hooks: Hooks,
 }
 
 impl World {
@@ -13,17 +41,96 @@ impl World {
     pub fn new() -> Self {
         Self {
             inner: hecs::World::new(),
+            names: FxHashMap::default(),
+            tags: FxHashMap::default(),
+            registry: TypeRegistry::with_builtins(),
+            templates: FxHashMap::default(),
+            hooks: Hooks::default(),
         }
     }
 
     /// Spawn an entity with the given components
     pub fn spawn(&mut self, components: impl hecs::DynamicBundle) -> Entity {
-        self.inner.spawn(components)
+        let hooked = self.bundle_hooks(&components);
+        let entity = self.inner.spawn(components);
+        self.index_entity(entity);
+
+        if !hooked.is_empty() {
+            let mut commands = Commands::new();
+            self.run_hooks(entity, &hooked, HookKind::Add, &mut commands);
+            self.run_hooks(entity, &hooked, HookKind::Insert, &mut commands);
+            commands.apply(self);
+        }
+        entity
     }
 
     /// Despawn an entity
     pub fn despawn(&mut self, entity: Entity) -> Result<(), hecs::NoSuchEntity> {
-        self.inner.despawn(entity)
+        let hooked = self.entity_hooks(entity);
+        let mut commands = Commands::new();
+        if !hooked.is_empty() {
+            self.run_hooks(entity, &hooked, HookKind::Remove, &mut commands);
+            self.run_hooks(entity, &hooked, HookKind::Despawn, &mut commands);
+        }
+
+        self.unindex_entity(entity);
+        let result = self.inner.despawn(entity);
+        commands.apply(self);
+        result
+    }
+
+    /// Add or replace components on an existing entity
+    pub fn insert(
+        &mut self,
+        entity: Entity,
+        components: impl hecs::DynamicBundle,
+    ) -> Result<(), hecs::NoSuchEntity> {
+        let hooked = self.bundle_hooks(&components);
+        let existing = self.entity_hooks(entity);
+
+        self.unindex_entity(entity);
+        let result = self.inner.insert(entity, components);
+        self.index_entity(entity);
+
+        if result.is_ok() && !hooked.is_empty() {
+            let added: Vec<TypeId> = hooked
+                .iter()
+                .filter(|id| !existing.contains(id))
+                .copied()
+                .collect();
+            let mut commands = Commands::new();
+            self.run_hooks(entity, &added, HookKind::Add, &mut commands);
+            self.run_hooks(entity, &hooked, HookKind::Insert, &mut commands);
+            commands.apply(self);
+        }
+        result
+    }
+
+    /// Add or replace a single component on an existing entity
+    pub fn insert_one(
+        &mut self,
+        entity: Entity,
+        component: impl hecs::Component,
+    ) -> Result<(), hecs::NoSuchEntity> {
+        self.insert(entity, (component,))
+    }
+
+    /// Remove a single component from an entity and return it
+    pub fn remove_one<T: hecs::Component>(
+        &mut self,
+        entity: Entity,
+    ) -> Result<T, hecs::ComponentError> {
+        let mut commands = Commands::new();
+        if self.inner.satisfies::<&T>(entity).unwrap_or(false) {
+            let hooked = self.hooks.hooked([TypeId::of::<T>()]);
+            self.run_hooks(entity, &hooked, HookKind::Remove, &mut commands);
+        }
+
+        self.unindex_entity(entity);
+        let result = self.inner.remove_one::<T>(entity);
+        self.index_entity(entity);
+        commands.apply(self);
+        result
     }
 
     /// Get a reference to a component
@@ -60,6 +167,8 @@ impl World {
     /// Clear all entities from the world
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.names.clear();
+        self.tags.clear();
     }
 
     /// Query for entities with specific components
@@ -71,6 +180,338 @@ impl World {
     pub fn query_mut<Q: hecs::Query>(&mut self) -> hecs::QueryMut<'_, Q> {
         self.inner.query_mut::<Q>()
     }
+
+    /// Set or replace the name of an entity
+    pub fn set_name(
+        &mut self,
+        entity: Entity,
+        name: impl Into<String>,
+    ) -> Result<(), hecs::NoSuchEntity> {
+        self.insert_one(entity, Name::new(name))
+    }
+
+    /// Find the first entity with the given name
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.find_all_by_name(name).next()
+    }
+
+    /// Find all entities with the given name
+    pub fn find_all_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Entity> + 'a {
+        let indexed = self
+            .names
+            .get(name)
+            .map_or(&[][..], |entities| entities.as_slice());
+        // Names changed in place through `get_mut` leave the index out of
+        // date, so fall back to a scan when it has nothing or a stale entry
+        let stale = indexed.is_empty() || indexed.iter().any(|&e| !self.has_name(e, name));
+        let scanned = stale.then(|| {
+            self.inner
+                .query::<&Name>()
+                .iter()
+                .filter(|(_, n)| n.as_str() == name)
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>()
+        });
+        let indexed = if stale { &[][..] } else { indexed };
+        indexed.iter().copied().chain(scanned.into_iter().flatten())
+    }
+
+    /// Find an entity by a hierarchical path such as `"Player/Weapon/Muzzle"`.
+    ///
+    /// The first segment must name a root entity (one without a [`Parent`]);
+    /// each following segment names a child of the previous entity.
+    pub fn find_by_path(&self, path: &str) -> Option<Entity> {
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        let first = segments.next()?;
+        let root = self
+            .find_all_by_name(first)
+            .find(|&entity| self.inner.get::<&Parent>(entity).is_err())?;
+        self.resolve_path(root, segments)
+    }
+
+    /// Find a descendant of `root` by a relative path such as `"Weapon/Muzzle"`
+    pub fn find_child_by_path(&self, root: Entity, path: &str) -> Option<Entity> {
+        self.resolve_path(root, path.split('/').filter(|s| !s.is_empty()))
+    }
+
+    /// Add a tag to an entity
+    ///
+    /// Returns false if the entity does not exist or already has the tag.
+    pub fn add_tag(&mut self, entity: Entity, tag: impl Into<String>) -> bool {
+        let tag = tag.into();
+        if !self.inner.contains(entity) {
+            return false;
+        }
+
+        let has_tags = self.inner.satisfies::<&Tags>(entity).unwrap_or(false);
+        let added = if has_tags {
+            self.inner
+                .get::<&mut Tags>(entity)
+                .is_ok_and(|mut tags| tags.insert(tag.clone()))
+        } else {
+            self.insert_one(entity, Tags::new().with(tag.clone()))
+                .is_ok()
+        };
+
+        if added {
+            self.tags.entry(tag).or_default().insert(entity);
+        }
+        added
+    }
+
+    /// Remove a tag from an entity, returns true if the tag was present
+    pub fn remove_tag(&mut self, entity: Entity, tag: &str) -> bool {
+        let removed = self
+            .inner
+            .get::<&mut Tags>(entity)
+            .is_ok_and(|mut tags| tags.remove(tag));
+
+        if removed {
+            self.unindex_tag(entity, tag);
+        }
+        removed
+    }
+
+    /// Check if an entity has a tag
+    pub fn has_tag(&self, entity: Entity, tag: &str) -> bool {
+        self.inner
+            .get::<&Tags>(entity)
+            .is_ok_and(|tags| tags.contains(tag))
+    }
+
+    /// Iterate over all entities with the given tag
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = Entity> + 'a {
+        self.tags
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |&entity| self.has_tag(entity, tag))
+    }
+
+    /// Get the number of entities with the given tag
+    pub fn tag_count(&self, tag: &str) -> usize {
+        self.tagged(tag).count()
+    }
+
+    /// Get the component type registry
+    pub fn registry(&self) -> &TypeRegistry {
+        &self.registry
+    }
+
+    /// Get the component type registry mutably
+    pub fn registry_mut(&mut self) -> &mut TypeRegistry {
+        &mut self.registry
+    }
+
+    /// Register a reflected component type
+    pub fn register_component<T: Reflect>(&mut self) {
+        self.registry.register::<T>();
+    }
+
+    /// List the names of all registered components on an entity
+    pub fn reflected_components(&self, entity: Entity) -> Vec<&'static str> {
+        self.registry
+            .iter()
+            .filter(|registration| registration.contains(&self.inner, entity))
+            .map(|registration| registration.name())
+            .collect()
+    }
+
+    /// Read a component field by dotted path, e.g. `("Transform", "position.x")`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component is not registered or the field does not exist
+    pub fn get_field(
+        &self,
+        entity: Entity,
+        component: &str,
+        path: &str,
+    ) -> Result<Value, ReflectError> {
+        self.registry
+            .get(component)
+            .ok_or_else(|| ReflectError::UnknownComponent(component.to_owned()))?
+            .get(&self.inner, entity, path)
+    }
+
+    /// Write a component field by dotted path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component is not registered, the field does not
+    /// exist or the value has the wrong type
+    pub fn set_field(
+        &mut self,
+        entity: Entity,
+        component: &str,
+        path: &str,
+        value: Value,
+    ) -> Result<(), ReflectError> {
+        let registration = *self
+            .registry
+            .get(component)
+            .ok_or_else(|| ReflectError::UnknownComponent(component.to_owned()))?;
+
+        self.unindex_entity(entity);
+        let result = registration.set(&mut self.inner, entity, path, value);
+        self.index_entity(entity);
+        result
+    }
+
+    /// Serialize all registered components of an entity
+    pub fn serialize_entity(&self, entity: Entity) -> Vec<ReflectedComponent> {
+        self.registry
+            .iter()
+            .filter(|registration| registration.contains(&self.inner, entity))
+            .map(|registration| ReflectedComponent {
+                component: registration.name().to_owned(),
+                fields: registration
+                    .fields()
+                    .iter()
+                    .filter_map(|field| {
+                        registration
+                            .get(&self.inner, entity, field.name)
+                            .ok()
+                            .map(|value| (field.name.to_owned(), value))
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Apply serialized components to an entity, inserting missing ones
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered; earlier components stay applied
+    pub fn deserialize_components(
+        &mut self,
+        entity: Entity,
+        components: &[ReflectedComponent],
+    ) -> Result<(), ReflectError> {
+        for serialized in components {
+            let registration = *self
+                .registry
+                .get(&serialized.component)
+                .ok_or_else(|| ReflectError::UnknownComponent(serialized.component.clone()))?;
+
+            self.unindex_entity(entity);
+            let result = Self::apply_reflected(&mut self.inner, &registration, entity, serialized);
+            self.index_entity(entity);
+            result?;
+        }
+        Ok(())
+    }
+
+    fn apply_reflected(
+        world: &mut hecs::World,
+        registration: &ComponentRegistration,
+        entity: Entity,
+        serialized: &ReflectedComponent,
+    ) -> Result<(), ReflectError> {
+        if !registration.contains(world, entity) {
+            registration.insert_default(world, entity)?;
+        }
+        for (field, value) in &serialized.fields {
+            registration.set(world, entity, field, value.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Hooked component types contained in a bundle
+    fn bundle_hooks(&self, bundle: &impl hecs::DynamicBundle) -> Vec<TypeId> {
+        if self.hooks.is_empty() {
+            return Vec::new();
+        }
+        bundle.with_ids(|ids| self.hooks.hooked(ids.iter().copied()))
+    }
+
+    /// Hooked component types currently on an entity
+    fn entity_hooks(&self, entity: Entity) -> Vec<TypeId> {
+        if self.hooks.is_empty() {
+            return Vec::new();
+        }
+        self.inner
+            .entity(entity)
+            .map(|entity_ref| self.hooks.hooked(entity_ref.component_types()))
+            .unwrap_or_default()
+    }
+
+    fn has_name(&self, entity: Entity, name: &str) -> bool {
+        self.inner
+            .get::<&Name>(entity)
+            .is_ok_and(|n| n.as_str() == name)
+    }
+
+    fn resolve_path<'a>(
+        &self,
+        root: Entity,
+        segments: impl Iterator<Item = &'a str>,
+    ) -> Option<Entity> {
+        let mut current = root;
+        for segment in segments {
+            current = self.find_all_by_name(segment).find(|&entity| {
+                self.inner
+                    .get::<&Parent>(entity)
+                    .is_ok_and(|parent| parent.entity() == current)
+            })?;
+        }
+        Some(current)
+    }
+
+    /// Add an entity's name and tags to the lookup indexes
+    fn index_entity(&mut self, entity: Entity) {
+        let Ok(entity_ref) = self.inner.entity(entity) else {
+            return;
+        };
+
+        if let Some(name) = entity_ref.get::<&Name>() {
+            let entities = self.names.entry(name.0.clone()).or_default();
+            if !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+
+        if let Some(tags) = entity_ref.get::<&Tags>() {
+            for tag in tags.iter() {
+                self.tags.entry(tag.to_owned()).or_default().insert(entity);
+            }
+        }
+    }
+
+    /// Remove an entity's name and tags from the lookup indexes
+    fn unindex_entity(&mut self, entity: Entity) {
+        let Ok(entity_ref) = self.inner.entity(entity) else {
+            return;
+        };
+
+        if let Some(name) = entity_ref.get::<&Name>()
+            && let Some(entities) = self.names.get_mut(name.as_str())
+        {
+            entities.retain(|e| *e != entity);
+            if entities.is_empty() {
+                self.names.remove(name.as_str());
+            }
+        }
+
+        let tags: SmallVec<[String; 4]> = entity_ref
+            .get::<&Tags>()
+            .map(|tags| tags.iter().map(str::to_owned).collect())
+            .unwrap_or_default();
+        for tag in &tags {
+            self.unindex_tag(entity, tag);
+        }
+    }
+
+    fn unindex_tag(&mut self, entity: Entity, tag: &str) {
+        if let Some(entities) = self.tags.get_mut(tag) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.tags.remove(tag);
+            }
+        }
+    }
 }
 
 impl Default for World {
@@ -78,3 +519,99 @@ impl Default for World {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Transform;
+
+    #[test]
+    fn test_find_by_name_tracks_rename_and_despawn() {
+        let mut world = World::new();
+        let player = world.spawn((Name::new("Player"),));
+
+        assert_eq!(world.find_by_name("Player"), Some(player));
+
+        world.set_name(player, "Hero").unwrap();
+        assert_eq!(world.find_by_name("Player"), None);
+        assert_eq!(world.find_by_name("Hero"), Some(player));
+
+        world.despawn(player).unwrap();
+        assert_eq!(world.find_by_name("Hero"), None);
+
+        // Renaming in place bypasses the index
+        let enemy = world.spawn((Name::new("Enemy"),));
+        world.get_mut::<Name>(enemy).unwrap().0 = "Boss".to_string();
+        assert_eq!(world.find_by_name("Enemy"), None);
+        assert_eq!(world.find_by_name("Boss"), Some(enemy));
+        assert_eq!(world.find_all_by_name("Boss").count(), 1);
+    }
+
+    #[test]
+    fn test_find_by_path() {
+        let mut world = World::new();
+        let player = world.spawn((Name::new("Player"),));
+        let weapon = world.spawn((Name::new("Weapon"), Parent::new(player)));
+        let muzzle = world.spawn((Name::new("Muzzle"), Parent::new(weapon)));
+        // Same name under a different parent must not match
+        let other = world.spawn((Name::new("Enemy"),));
+        world.spawn((Name::new("Weapon"), Parent::new(other)));
+
+        assert_eq!(world.find_by_path("Player/Weapon/Muzzle"), Some(muzzle));
+        assert_eq!(world.find_child_by_path(player, "Weapon"), Some(weapon));
+        assert_eq!(world.find_by_path("Weapon"), None);
+        assert_eq!(world.find_by_path("Player/Muzzle"), None);
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut world = World::new();
+        let a = world.spawn((Tags::new().with("enemy"),));
+        let b = world.spawn(());
+
+        assert!(world.add_tag(b, "enemy"));
+        assert!(!world.add_tag(b, "enemy"));
+        assert_eq!(world.tag_count("enemy"), 2);
+
+        assert!(world.remove_tag(a, "enemy"));
+        assert_eq!(world.tagged("enemy").collect::<Vec<_>>(), vec![b]);
+
+        world.despawn(b).unwrap();
+        assert_eq!(world.tag_count("enemy"), 0);
+
+        // The first tag inserts the component, which runs hooks
+        world.on_add::<Tags>(|_, entity, commands| {
+            commands.insert(entity, (Name::new("Tagged"),));
+        });
+        let c = world.spawn(());
+        assert!(world.add_tag(c, "enemy"));
+        assert_eq!(world.find_by_name("Tagged"), Some(c));
+    }
+
+    #[test]
+    fn test_reflected_fields() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::default(), Name::new("Crate")));
+
+        assert_eq!(
+            world.reflected_components(entity),
+            vec!["Transform", "Name"]
+        );
+
+        world
+            .set_field(entity, "Transform", "position.y", Value::F32(3.0))
+            .unwrap();
+        assert_eq!(world.get::<Transform>(entity).unwrap().position.y, 3.0);
+
+        world
+            .set_field(entity, "Name", "name", Value::String("Barrel".into()))
+            .unwrap();
+        assert_eq!(world.find_by_name("Barrel"), Some(entity));
+
+        let serialized = world.serialize_entity(entity);
+        let copy = world.spawn(());
+        world.deserialize_components(copy, &serialized).unwrap();
+        assert_eq!(world.get::<Transform>(copy).unwrap().position.y, 3.0);
+        assert_eq!(world.find_all_by_name("Barrel").count(), 2);
+    }
+}
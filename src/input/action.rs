@@ -0,0 +1,726 @@
+//! Named input actions and axes
+//!
+//! An [`ActionMap`] maps action names such as `"jump"` or `"move"` to physical
+//! inputs, so gameplay code never refers to specific keys and controls can be
+//! rebound and saved at runtime.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+use super::gamepad::{GamepadAxis, GamepadButton};
+use super::state::Input;
+
+/// A physical digital input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    /// Keyboard key
+    Key(KeyCode),
+    /// Mouse button
+    Mouse(MouseButton),
+    /// Button on any connected gamepad
+    Gamepad(GamepadButton),
+    /// Scroll wheel moved up this frame
+    ScrollUp,
+    /// Scroll wheel moved down this frame
+    ScrollDown,
+    /// Scroll wheel moved left this frame
+    ScrollLeft,
+    /// Scroll wheel moved right this frame
+    ScrollRight,
+}
+
+impl Binding {
+    /// Capture the first key, mouse button or gamepad button pressed this frame
+    ///
+    /// Useful for "press a key to rebind" menus.
+    #[must_use]
+    pub fn capture(input: &Input) -> Option<Self> {
+        input
+            .just_pressed_keys()
+            .next()
+            .map(Self::Key)
+            .or_else(|| input.just_pressed_mouse_buttons().next().map(Self::Mouse))
+            .or_else(|| {
+                input
+                    .gamepads()
+                    .iter()
+                    .find_map(|(_, pad)| pad.just_pressed_buttons().next())
+                    .map(Self::Gamepad)
+            })
+    }
+
+    /// Check if the binding is held
+    #[must_use]
+    pub fn is_pressed(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_key_pressed(key),
+            Self::Mouse(button) => input.is_mouse_button_pressed(button),
+            Self::Gamepad(button) => {
+                !input.is_gamepad_button_consumed(button) && input.gamepads().any_pressed(button)
+            }
+            Self::ScrollUp => input.scroll_delta().y > 0.0,
+            Self::ScrollDown => input.scroll_delta().y < 0.0,
+            Self::ScrollLeft => input.scroll_delta().x < 0.0,
+            Self::ScrollRight => input.scroll_delta().x > 0.0,
+        }
+    }
+
+    /// Check if the binding was pressed this frame
+    ///
+    /// Scroll bindings count as pressed on every frame the wheel moves.
+    #[must_use]
+    pub fn is_just_pressed(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_key_just_pressed(key),
+            Self::Mouse(button) => input.is_mouse_button_just_pressed(button),
+            Self::Gamepad(button) => {
+                !input.is_gamepad_button_consumed(button)
+                    && input.gamepads().any_just_pressed(button)
+            }
+            _ => self.is_pressed(input),
+        }
+    }
+
+    /// Check if the binding was released this frame
+    #[must_use]
+    pub fn is_just_released(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_key_just_released(key),
+            Self::Mouse(button) => input.is_mouse_button_just_released(button),
+            Self::Gamepad(button) => {
+                !input.is_gamepad_button_consumed(button)
+                    && input.gamepads().any_just_released(button)
+            }
+            _ => false,
+        }
+    }
+
+    /// Consume the binding's input for the rest of this frame
+    pub fn consume(self, input: &mut Input) {
+        match self {
+            Self::Key(key) => input.consume_key(key),
+            Self::Mouse(button) => input.consume_mouse_button(button),
+            Self::Gamepad(button) => input.consume_gamepad_button(button),
+            Self::ScrollUp | Self::ScrollDown | Self::ScrollLeft | Self::ScrollRight => {
+                input.consume_scroll();
+            }
+        }
+    }
+
+    fn value(self, input: &Input) -> f32 {
+        if self.is_pressed(input) { 1.0 } else { 0.0 }
+    }
+}
+
+/// A source for a one-dimensional axis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    /// Two digital inputs mapped to -1.0 and 1.0
+    Digital {
+        negative: Binding,
+        positive: Binding,
+    },
+    /// Gamepad stick or trigger, with deadzone and curve applied
+    Gamepad(GamepadAxis),
+    /// Horizontal mouse look motion this frame (see [`Input::mouse_look_delta`])
+    MouseX,
+    /// Vertical mouse look motion this frame (see [`Input::mouse_look_delta`])
+    MouseY,
+    /// Horizontal scroll this frame
+    ScrollX,
+    /// Vertical scroll this frame
+    ScrollY,
+}
+
+impl AxisBinding {
+    pub(super) fn value(self, input: &Input) -> f32 {
+        match self {
+            Self::Digital { negative, positive } => positive.value(input) - negative.value(input),
+            Self::Gamepad(axis) => input.gamepad_axis(axis),
+            Self::MouseX => input.mouse_look_delta().x,
+            Self::MouseY => input.mouse_look_delta().y,
+            Self::ScrollX => input.scroll_delta().x,
+            Self::ScrollY => input.scroll_delta().y,
+        }
+    }
+
+    pub(super) fn digital_bindings(&self) -> Vec<Binding> {
+        match *self {
+            Self::Digital { negative, positive } => vec![negative, positive],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Consume the analog input read by the binding for the rest of this frame
+    ///
+    /// Digital bindings are consumed key by key through [`Binding::consume`].
+    pub(super) fn consume_analog(self, input: &mut Input) {
+        match self {
+            Self::Digital { .. } => {}
+            Self::Gamepad(axis) => input.consume_gamepad_axis(axis),
+            Self::MouseX | Self::MouseY => input.consume_mouse_motion(),
+            Self::ScrollX | Self::ScrollY => input.consume_scroll(),
+        }
+    }
+}
+
+/// A source for a two-dimensional axis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Axis2dBinding {
+    /// Four digital inputs such as WASD; positive Y is up
+    ///
+    /// The result is clamped to unit length so diagonals aren't faster.
+    Composite {
+        up: Binding,
+        down: Binding,
+        left: Binding,
+        right: Binding,
+    },
+    /// Left stick of any connected gamepad
+    LeftStick,
+    /// Right stick of any connected gamepad
+    RightStick,
+    /// Mouse look motion this frame (see [`Input::mouse_look_delta`])
+    MouseDelta,
+    /// Scroll wheel movement this frame
+    Scroll,
+}
+
+impl Axis2dBinding {
+    /// WASD composite binding
+    #[must_use]
+    pub fn wasd() -> Self {
+        Self::Composite {
+            up: Binding::Key(KeyCode::KeyW),
+            down: Binding::Key(KeyCode::KeyS),
+            left: Binding::Key(KeyCode::KeyA),
+            right: Binding::Key(KeyCode::KeyD),
+        }
+    }
+
+    /// Arrow keys composite binding
+    #[must_use]
+    pub fn arrows() -> Self {
+        Self::Composite {
+            up: Binding::Key(KeyCode::ArrowUp),
+            down: Binding::Key(KeyCode::ArrowDown),
+            left: Binding::Key(KeyCode::ArrowLeft),
+            right: Binding::Key(KeyCode::ArrowRight),
+        }
+    }
+
+    pub(super) fn value(self, input: &Input) -> Vec2 {
+        match self {
+            Self::Composite {
+                up,
+                down,
+                left,
+                right,
+            } => Vec2::new(
+                right.value(input) - left.value(input),
+                up.value(input) - down.value(input),
+            )
+            .clamp_length_max(1.0),
+            Self::LeftStick => Vec2::new(
+                input.gamepad_axis(GamepadAxis::LeftStickX),
+                input.gamepad_axis(GamepadAxis::LeftStickY),
+            ),
+            Self::RightStick => Vec2::new(
+                input.gamepad_axis(GamepadAxis::RightStickX),
+                input.gamepad_axis(GamepadAxis::RightStickY),
+            ),
+            Self::MouseDelta => input.mouse_look_delta(),
+            Self::Scroll => input.scroll_delta(),
+        }
+    }
+
+    pub(super) fn digital_bindings(&self) -> Vec<Binding> {
+        match *self {
+            Self::Composite {
+                up,
+                down,
+                left,
+                right,
+            } => vec![up, down, left, right],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Consume the analog input read by the binding for the rest of this frame
+    ///
+    /// Digital bindings are consumed key by key through [`Binding::consume`].
+    pub(super) fn consume_analog(self, input: &mut Input) {
+        match self {
+            Self::Composite { .. } => {}
+            Self::LeftStick => {
+                input.consume_gamepad_axis(GamepadAxis::LeftStickX);
+                input.consume_gamepad_axis(GamepadAxis::LeftStickY);
+            }
+            Self::RightStick => {
+                input.consume_gamepad_axis(GamepadAxis::RightStickX);
+                input.consume_gamepad_axis(GamepadAxis::RightStickY);
+            }
+            Self::MouseDelta => input.consume_mouse_motion(),
+            Self::Scroll => input.consume_scroll(),
+        }
+    }
+}
+
+/// A digital input bound to more than one action or axis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingConflict {
+    /// The shared input
+    pub binding: Binding,
+    /// Names of the actions and axes using it
+    pub users: Vec<String>,
+}
+
+/// Maps action and axis names to physical inputs
+///
+/// ```ignore
+/// let actions = ActionMap::new()
+///     .with_action("jump", [Binding::Key(KeyCode::Space)])
+///     .with_axis_2d("move", [Axis2dBinding::wasd(), Axis2dBinding::arrows()]);
+///
+/// if actions.just_pressed(&ctx.input, "jump") { /* ... */ }
+/// let movement = actions.axis_2d(&ctx.input, "move");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionMap {
+    /// Digital actions
+    #[serde(default)]
+    actions: BTreeMap<String, Vec<Binding>>,
+    /// One-dimensional axes
+    #[serde(default)]
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    /// Two-dimensional axes
+    #[serde(default)]
+    axes_2d: BTreeMap<String, Vec<Axis2dBinding>>,
+}
+
+impl ActionMap {
+    /// Create an empty action map
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add bindings to an action (builder style)
+    #[must_use]
+    pub fn with_action(
+        mut self,
+        action: impl Into<String>,
+        bindings: impl IntoIterator<Item = Binding>,
+    ) -> Self {
+        let action = action.into();
+        for binding in bindings {
+            self.bind(action.clone(), binding);
+        }
+        self
+    }
+
+    /// Add bindings to an axis (builder style)
+    #[must_use]
+    pub fn with_axis(
+        mut self,
+        axis: impl Into<String>,
+        bindings: impl IntoIterator<Item = AxisBinding>,
+    ) -> Self {
+        self.axes.entry(axis.into()).or_default().extend(bindings);
+        self
+    }
+
+    /// Add bindings to a 2D axis (builder style)
+    #[must_use]
+    pub fn with_axis_2d(
+        mut self,
+        axis: impl Into<String>,
+        bindings: impl IntoIterator<Item = Axis2dBinding>,
+    ) -> Self {
+        self.axes_2d
+            .entry(axis.into())
+            .or_default()
+            .extend(bindings);
+        self
+    }
+
+    /// Add a binding to an action
+    pub fn bind(&mut self, action: impl Into<String>, binding: Binding) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Remove a binding from an action, returns true if it was bound
+    pub fn unbind(&mut self, action: &str, binding: Binding) -> bool {
+        let Some(bindings) = self.actions.get_mut(action) else {
+            return false;
+        };
+        let len = bindings.len();
+        bindings.retain(|&b| b != binding);
+        bindings.len() != len
+    }
+
+    /// Replace a binding of an action, keeping its position
+    ///
+    /// Returns false if the action did not use `old`.
+    pub fn rebind(&mut self, action: &str, old: Binding, new: Binding) -> bool {
+        let Some(bindings) = self.actions.get_mut(action) else {
+            return false;
+        };
+        if !bindings.contains(&old) {
+            return false;
+        }
+        if old != new {
+            bindings.retain(|&b| b != new);
+        }
+        if let Some(index) = bindings.iter().position(|&b| b == old) {
+            bindings[index] = new;
+        }
+        true
+    }
+
+    /// Remove all bindings of an action
+    pub fn clear_action(&mut self, action: &str) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.clear();
+        }
+    }
+
+    /// Get the bindings of an action
+    #[must_use]
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the bindings of an axis
+    #[must_use]
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get mutable bindings of an axis, creating it if needed
+    pub fn axis_bindings_mut(&mut self, axis: impl Into<String>) -> &mut Vec<AxisBinding> {
+        self.axes.entry(axis.into()).or_default()
+    }
+
+    /// Get the bindings of a 2D axis
+    #[must_use]
+    pub fn axis_2d_bindings(&self, axis: &str) -> &[Axis2dBinding] {
+        self.axes_2d.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get mutable bindings of a 2D axis, creating it if needed
+    pub fn axis_2d_bindings_mut(&mut self, axis: impl Into<String>) -> &mut Vec<Axis2dBinding> {
+        self.axes_2d.entry(axis.into()).or_default()
+    }
+
+    /// Iterate over action names
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// Iterate over axis and 2D axis names
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes
+            .keys()
+            .chain(self.axes_2d.keys())
+            .map(String::as_str)
+    }
+
+    /// Check if any binding of an action is held
+    #[must_use]
+    pub fn pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.is_pressed(input))
+    }
+
+    /// Check if an action was pressed this frame
+    #[must_use]
+    pub fn just_pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|b| b.is_just_pressed(input))
+    }
+
+    /// Check if an action was released this frame and no other binding holds it
+    #[must_use]
+    pub fn just_released(&self, input: &Input, action: &str) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|b| b.is_just_released(input))
+            && !bindings.iter().any(|b| b.is_pressed(input))
+    }
+
+    /// Get the value of an axis
+    ///
+    /// Values of all bindings are summed; keys and gamepad axes are clamped
+    /// together to -1.0..=1.0 while mouse and scroll deltas are passed through.
+    #[must_use]
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        let (digital, analog): (Vec<&AxisBinding>, Vec<_>) = self
+            .axis_bindings(axis)
+            .iter()
+            .partition(|b| matches!(b, AxisBinding::Digital { .. } | AxisBinding::Gamepad(_)));
+        let digital: f32 = digital.iter().map(|b| b.value(input)).sum();
+        let analog: f32 = analog.iter().map(|b| b.value(input)).sum();
+        digital.clamp(-1.0, 1.0) + analog
+    }
+
+    /// Get the value of a 2D axis
+    ///
+    /// Values of all bindings are summed; keys and gamepad sticks are clamped
+    /// together to unit length while mouse and scroll deltas are passed through.
+    #[must_use]
+    pub fn axis_2d(&self, input: &Input, axis: &str) -> Vec2 {
+        let (digital, analog): (Vec<&Axis2dBinding>, Vec<_>) =
+            self.axis_2d_bindings(axis).iter().partition(|b| {
+                matches!(
+                    b,
+                    Axis2dBinding::Composite { .. }
+                        | Axis2dBinding::LeftStick
+                        | Axis2dBinding::RightStick
+                )
+            });
+        let digital: Vec2 = digital.iter().map(|b| b.value(input)).sum();
+        let analog: Vec2 = analog.iter().map(|b| b.value(input)).sum();
+        digital.clamp_length_max(1.0) + analog
+    }
+
+    /// Get the names of all actions and axes using a digital input
+    #[must_use]
+    pub fn users_of(&self, binding: Binding) -> Vec<&str> {
+        let actions = self
+            .actions
+            .iter()
+            .filter(|(_, bindings)| bindings.contains(&binding))
+            .map(|(name, _)| name.as_str());
+        let axes = self
+            .axes
+            .iter()
+            .filter(|(_, bindings)| {
+                bindings
+                    .iter()
+                    .any(|b| b.digital_bindings().contains(&binding))
+            })
+            .map(|(name, _)| name.as_str());
+        let axes_2d = self
+            .axes_2d
+            .iter()
+            .filter(|(_, bindings)| {
+                bindings
+                    .iter()
+                    .any(|b| b.digital_bindings().contains(&binding))
+            })
+            .map(|(name, _)| name.as_str());
+        actions.chain(axes).chain(axes_2d).collect()
+    }
+
+    /// Find digital inputs used by more than one action or axis
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let mut all: Vec<Binding> = self.actions.values().flatten().copied().collect();
+        all.extend(
+            self.axes
+                .values()
+                .flatten()
+                .flat_map(AxisBinding::digital_bindings),
+        );
+        all.extend(
+            self.axes_2d
+                .values()
+                .flatten()
+                .flat_map(Axis2dBinding::digital_bindings),
+        );
+
+        let mut conflicts: Vec<BindingConflict> = Vec::new();
+        for binding in all {
+            if conflicts.iter().any(|c| c.binding == binding) {
+                continue;
+            }
+            let users = self.users_of(binding);
+            if users.len() > 1 {
+                conflicts.push(BindingConflict {
+                    binding,
+                    users: users.into_iter().map(String::from).collect(),
+                });
+            }
+        }
+        conflicts
+    }
+
+    /// Serialize to a RON string
+    pub fn to_ron(&self) -> Result<String, ActionMapError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| ActionMapError::SerializeError(e.to_string()))
+    }
+
+    /// Deserialize from a RON string
+    pub fn from_ron(content: &str) -> Result<Self, ActionMapError> {
+        ron::from_str(content).map_err(|e| ActionMapError::DeserializeError(e.to_string()))
+    }
+
+    /// Serialize to a JSON string
+    pub fn to_json(&self) -> Result<String, ActionMapError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ActionMapError::SerializeError(e.to_string()))
+    }
+
+    /// Deserialize from a JSON string
+    pub fn from_json(content: &str) -> Result<Self, ActionMapError> {
+        serde_json::from_str(content).map_err(|e| ActionMapError::DeserializeError(e.to_string()))
+    }
+
+    /// Save to a RON file
+    pub fn save_ron(&self, path: impl AsRef<Path>) -> Result<(), ActionMapError> {
+        fs::write(path, self.to_ron()?).map_err(|e| ActionMapError::IoError(e.to_string()))
+    }
+
+    /// Load from a RON file
+    pub fn load_ron(path: impl AsRef<Path>) -> Result<Self, ActionMapError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| ActionMapError::IoError(e.to_string()))?;
+        Self::from_ron(&content)
+    }
+
+    /// Save to a JSON file
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), ActionMapError> {
+        fs::write(path, self.to_json()?).map_err(|e| ActionMapError::IoError(e.to_string()))
+    }
+
+    /// Load from a JSON file
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self, ActionMapError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| ActionMapError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+}
+
+/// Action map persistence errors
+#[derive(Debug)]
+pub enum ActionMapError {
+    /// IO error
+    IoError(String),
+    /// Serialization error
+    SerializeError(String),
+    /// Deserialization error
+    DeserializeError(String),
+}
+
+impl std::fmt::Display for ActionMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "IO error: {e}"),
+            Self::SerializeError(e) => write!(f, "Serialization error: {e}"),
+            Self::DeserializeError(e) => write!(f, "Deserialization error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ActionMapError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::ElementState;
+
+    fn sample_map() -> ActionMap {
+        ActionMap::new()
+            .with_action(
+                "jump",
+                [
+                    Binding::Key(KeyCode::Space),
+                    Binding::Mouse(MouseButton::Right),
+                ],
+            )
+            .with_axis_2d("move", [Axis2dBinding::wasd(), Axis2dBinding::arrows()])
+            .with_axis(
+                "zoom",
+                [
+                    AxisBinding::Digital {
+                        negative: Binding::Key(KeyCode::Minus),
+                        positive: Binding::Key(KeyCode::Equal),
+                    },
+                    AxisBinding::ScrollY,
+                ],
+            )
+    }
+
+    #[test]
+    fn test_actions_and_axes() {
+        let map = sample_map();
+        let mut input = Input::new();
+
+        input.process_mouse_button(MouseButton::Right, ElementState::Pressed);
+        input.process_keyboard(KeyCode::KeyW, ElementState::Pressed);
+        input.process_keyboard(KeyCode::KeyD, ElementState::Pressed);
+        input.process_keyboard(KeyCode::ArrowUp, ElementState::Pressed);
+        input.process_scroll(Vec2::new(0.0, 2.0));
+
+        assert!(map.just_pressed(&input, "jump"));
+        let movement = map.axis_2d(&input, "move");
+        assert!((movement.length() - 1.0).abs() < 0.001);
+        assert!(movement.x > 0.0 && movement.y > movement.x);
+        assert_eq!(map.axis(&input, "zoom"), 2.0);
+        assert_eq!(map.axis(&input, "missing"), 0.0);
+
+        input.update();
+        input.process_mouse_button(MouseButton::Right, ElementState::Released);
+        assert!(map.just_released(&input, "jump"));
+        assert!(!map.pressed(&input, "jump"));
+    }
+
+    #[test]
+    fn test_rebind_and_conflicts() {
+        let mut map = sample_map();
+        assert!(map.conflicts().is_empty());
+
+        assert!(map.rebind(
+            "jump",
+            Binding::Key(KeyCode::Space),
+            Binding::Key(KeyCode::KeyW)
+        ));
+        assert_eq!(
+            map.users_of(Binding::Key(KeyCode::KeyW)),
+            vec!["jump", "move"]
+        );
+        let conflicts = map.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].binding, Binding::Key(KeyCode::KeyW));
+
+        assert!(map.unbind("jump", Binding::Key(KeyCode::KeyW)));
+        assert!(map.conflicts().is_empty());
+        assert_eq!(map.bindings("jump"), &[Binding::Mouse(MouseButton::Right)]);
+
+        // Rebinding onto a binding the action already has doesn't duplicate it
+        let (a, b, c) = (
+            Binding::Key(KeyCode::KeyA),
+            Binding::Key(KeyCode::KeyB),
+            Binding::Key(KeyCode::KeyC),
+        );
+        let mut map = ActionMap::new();
+        for binding in [a, b, c] {
+            map.bind("fire", binding);
+        }
+        assert!(map.rebind("fire", a, c));
+        assert_eq!(map.bindings("fire"), &[c, b]);
+        assert!(map.rebind("fire", b, b));
+        assert_eq!(map.bindings("fire"), &[c, b]);
+    }
+
+    #[test]
+    fn test_ron_and_json_round_trip() {
+        let map = sample_map();
+        assert_eq!(ActionMap::from_ron(&map.to_ron().unwrap()).unwrap(), map);
+        assert_eq!(ActionMap::from_json(&map.to_json().unwrap()).unwrap(), map);
+        assert!(matches!(
+            ActionMap::from_json("{ nope"),
+            Err(ActionMapError::DeserializeError(_))
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+//! Layered input contexts
+//!
+//! An [`InputContextStack`] holds contexts such as console, UI and gameplay,
+//! each with its own [`ActionMap`]. Contexts higher on the stack see input
+//! first and consume whatever their actions use, so lower contexts and plain
+//! [`Input`] queries don't react to the same key or click.
+
+use std::collections::HashSet;
+
+use glam::Vec2;
+use rustc_hash::FxHashMap;
+
+use super::action::{ActionMap, Binding};
+use super::state::Input;
+
+/// Action and axis values of a context for the current frame
+#[derive(Debug, Clone, Default)]
+struct ContextState {
+    pressed: HashSet<String>,
+    just_pressed: HashSet<String>,
+    just_released: HashSet<String>,
+    axes: FxHashMap<String, f32>,
+    axes_2d: FxHashMap<String, Vec2>,
+}
+
+/// A named layer of input handling with its own actions
+#[derive(Debug, Clone)]
+pub struct InputContext {
+    /// Context name, e.g. `"ui"`
+    pub name: String,
+    /// Actions read by this context
+    pub actions: ActionMap,
+    /// Consume all input so lower contexts see nothing
+    pub blocking: bool,
+    state: ContextState,
+}
+
+impl InputContext {
+    /// Create a context that consumes only the inputs its actions use
+    #[must_use]
+    pub fn new(name: impl Into<String>, actions: ActionMap) -> Self {
+        Self {
+            name: name.into(),
+            actions,
+            blocking: false,
+            state: ContextState::default(),
+        }
+    }
+
+    /// Make the context consume all input, e.g. for a console or modal menu
+    #[must_use]
+    pub fn blocking(mut self) -> Self {
+        self.blocking = true;
+        self
+    }
+
+    /// Read this frame's action and axis values, then consume the inputs used
+    fn update(&mut self, input: &mut Input) {
+        let mut state = ContextState::default();
+        let mut used = Vec::new();
+        let mut used_axes = Vec::new();
+        let mut used_axes_2d = Vec::new();
+
+        for action in self.actions.actions() {
+            let bindings = self.actions.bindings(action);
+            if self.actions.pressed(input, action) {
+                state.pressed.insert(action.to_string());
+            }
+            if self.actions.just_pressed(input, action) {
+                state.just_pressed.insert(action.to_string());
+            }
+            if self.actions.just_released(input, action) {
+                state.just_released.insert(action.to_string());
+            }
+            used.extend(bindings.iter().copied().filter(|b| is_active(*b, input)));
+        }
+
+        for axis in self.actions.axes() {
+            let bindings = self.actions.axis_bindings(axis);
+            if !bindings.is_empty() {
+                state
+                    .axes
+                    .insert(axis.to_string(), self.actions.axis(input, axis));
+                used.extend(
+                    bindings
+                        .iter()
+                        .flat_map(|b| b.digital_bindings())
+                        .filter(|b| b.is_pressed(input)),
+                );
+                used_axes.extend(bindings.iter().copied().filter(|b| b.value(input) != 0.0));
+            }
+            let bindings = self.actions.axis_2d_bindings(axis);
+            if !bindings.is_empty() {
+                state
+                    .axes_2d
+                    .insert(axis.to_string(), self.actions.axis_2d(input, axis));
+                used.extend(
+                    bindings
+                        .iter()
+                        .flat_map(|b| b.digital_bindings())
+                        .filter(|b| b.is_pressed(input)),
+                );
+                used_axes_2d.extend(
+                    bindings
+                        .iter()
+                        .copied()
+                        .filter(|b| b.value(input) != Vec2::ZERO),
+                );
+            }
+        }
+
+        for binding in used {
+            binding.consume(input);
+        }
+        for binding in used_axes {
+            binding.consume_analog(input);
+        }
+        for binding in used_axes_2d {
+            binding.consume_analog(input);
+        }
+        if self.blocking {
+            input.consume_all();
+        }
+        self.state = state;
+    }
+}
+
+/// Check if a binding is held, pressed or released this frame
+fn is_active(binding: Binding, input: &Input) -> bool {
+    binding.is_pressed(input) || binding.is_just_pressed(input) || binding.is_just_released(input)
+}
+
+/// A stack of input contexts, topmost first to receive input
+///
+/// Call [`InputContextStack::update`] once per frame after input events have
+/// been processed; the engine does this for
+/// [`EngineContext`](crate::core::EngineContext) before
+/// [`EngineContext::actions`](crate::core::EngineContext::actions) and game code
+/// read input, so both only see what the contexts left unconsumed.
+///
+/// ```ignore
+/// ctx.input_contexts.push(InputContext::new("ui", ui_actions));
+/// ctx.input_contexts.push(InputContext::new("console", console_actions).blocking());
+///
+/// if ctx.input_contexts.just_pressed("ui", "confirm") { /* ... */ }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputContextStack {
+    /// Contexts from bottom to top
+    contexts: Vec<InputContext>,
+}
+
+impl InputContextStack {
+    /// Create an empty stack
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a context on top, replacing any context with the same name
+    pub fn push(&mut self, context: InputContext) {
+        self.remove(&context.name);
+        self.contexts.push(context);
+    }
+
+    /// Pop the topmost context
+    pub fn pop(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    /// Remove a context by name
+    pub fn remove(&mut self, name: &str) -> Option<InputContext> {
+        let index = self.contexts.iter().position(|c| c.name == name)?;
+        Some(self.contexts.remove(index))
+    }
+
+    /// Get the topmost context
+    #[must_use]
+    pub fn top(&self) -> Option<&InputContext> {
+        self.contexts.last()
+    }
+
+    /// Get a context by name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&InputContext> {
+        self.contexts.iter().find(|c| c.name == name)
+    }
+
+    /// Get a context by name mutably, e.g. to rebind its actions
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut InputContext> {
+        self.contexts.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Check if a context is on the stack
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Iterate over contexts, topmost first
+    pub fn iter(&self) -> impl Iterator<Item = &InputContext> {
+        self.contexts.iter().rev()
+    }
+
+    /// Get the number of contexts
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Check if the stack is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Remove all contexts
+    pub fn clear(&mut self) {
+        self.contexts.clear();
+    }
+
+    /// Let each context read input from the top down, consuming what it uses
+    pub fn update(&mut self, input: &mut Input) {
+        for context in self.contexts.iter_mut().rev() {
+            context.update(input);
+        }
+    }
+
+    /// Check if an action of a context is held
+    #[must_use]
+    pub fn pressed(&self, context: &str, action: &str) -> bool {
+        self.get(context)
+            .is_some_and(|c| c.state.pressed.contains(action))
+    }
+
+    /// Check if an action of a context was pressed this frame
+    #[must_use]
+    pub fn just_pressed(&self, context: &str, action: &str) -> bool {
+        self.get(context)
+            .is_some_and(|c| c.state.just_pressed.contains(action))
+    }
+
+    /// Check if an action of a context was released this frame
+    #[must_use]
+    pub fn just_released(&self, context: &str, action: &str) -> bool {
+        self.get(context)
+            .is_some_and(|c| c.state.just_released.contains(action))
+    }
+
+    /// Get the value of an axis of a context
+    #[must_use]
+    pub fn axis(&self, context: &str, axis: &str) -> f32 {
+        self.get(context)
+            .and_then(|c| c.state.axes.get(axis).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Get the value of a 2D axis of a context
+    #[must_use]
+    pub fn axis_2d(&self, context: &str, axis: &str) -> Vec2 {
+        self.get(context)
+            .and_then(|c| c.state.axes_2d.get(axis).copied())
+            .unwrap_or(Vec2::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{Axis2dBinding, AxisBinding};
+    use winit::event::{ElementState, MouseButton};
+    use winit::keyboard::KeyCode;
+
+    fn stack() -> InputContextStack {
+        let mut stack = InputContextStack::new();
+        stack.push(InputContext::new(
+            "gameplay",
+            ActionMap::new()
+                .with_action("fire", [Binding::Mouse(MouseButton::Left)])
+                .with_action("jump", [Binding::Key(KeyCode::Space)])
+                .with_axis_2d("move", [Axis2dBinding::wasd()]),
+        ));
+        stack.push(InputContext::new(
+            "ui",
+            ActionMap::new()
+                .with_action("click", [Binding::Mouse(MouseButton::Left)])
+                .with_axis_2d("navigate", [Axis2dBinding::wasd()]),
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_higher_context_consumes() {
+        let mut stack = stack();
+        let mut input = Input::new();
+        input.process_mouse_button(MouseButton::Left, ElementState::Pressed);
+        input.process_keyboard(KeyCode::Space, ElementState::Pressed);
+        input.process_keyboard(KeyCode::KeyW, ElementState::Pressed);
+        stack.update(&mut input);
+
+        assert!(stack.just_pressed("ui", "click"));
+        assert!(!stack.just_pressed("gameplay", "fire"));
+        assert!(!input.is_mouse_button_just_pressed(MouseButton::Left));
+        assert_eq!(stack.axis_2d("ui", "navigate"), Vec2::Y);
+        assert_eq!(stack.axis_2d("gameplay", "move"), Vec2::ZERO);
+
+        // Unused by the UI, so gameplay still gets it
+        assert!(stack.just_pressed("gameplay", "jump"));
+        assert!(input.is_key_consumed(KeyCode::Space));
+        assert!(!input.is_key_consumed(KeyCode::KeyE));
+
+        // Consumption only lasts for the frame
+        input.update();
+        assert!(input.is_mouse_button_pressed(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_blocking_context_and_pop() {
+        let mut stack = stack();
+        stack.push(InputContext::new("console", ActionMap::new()).blocking());
+        assert_eq!(stack.top().map(|c| c.name.as_str()), Some("console"));
+
+        let mut input = Input::new();
+        input.process_keyboard(KeyCode::Space, ElementState::Pressed);
+        stack.update(&mut input);
+        assert!(!stack.just_pressed("gameplay", "jump"));
+        assert!(!input.is_key_pressed(KeyCode::Space));
+
+        // Closing the console hands input back
+        stack.pop();
+        input.update();
+        stack.update(&mut input);
+        assert!(stack.pressed("gameplay", "jump"));
+        assert_eq!(
+            stack.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            ["ui", "gameplay"]
+        );
+    }
+
+    #[test]
+    fn test_contexts_consume_analog_input() {
+        let mut stack = InputContextStack::new();
+        stack.push(InputContext::new(
+            "gameplay",
+            ActionMap::new()
+                .with_axis("turn", [AxisBinding::MouseX])
+                .with_axis("zoom", [AxisBinding::ScrollY]),
+        ));
+        let mut input = Input::new();
+        input.process_mouse_delta(Vec2::new(4.0, 2.0));
+        input.process_scroll(Vec2::Y);
+        stack.update(&mut input);
+        assert!(stack.axis("gameplay", "turn") > 0.0);
+        assert_eq!(stack.axis("gameplay", "zoom"), 1.0);
+
+        // A blocking context zeroes mouse motion and axes for the context below
+        stack.push(InputContext::new("console", ActionMap::new()).blocking());
+        stack.update(&mut input);
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.mouse_look_delta(), Vec2::ZERO);
+        assert_eq!(stack.axis("gameplay", "turn"), 0.0);
+        assert_eq!(stack.axis("gameplay", "zoom"), 0.0);
+
+        // A context that reads mouse motion consumes it as well
+        stack.pop();
+        stack.push(InputContext::new(
+            "camera",
+            ActionMap::new().with_axis_2d("look", [Axis2dBinding::MouseDelta]),
+        ));
+        input.update();
+        input.process_mouse_delta(Vec2::new(4.0, 2.0));
+        stack.update(&mut input);
+        assert_ne!(stack.axis_2d("camera", "look"), Vec2::ZERO);
+        assert_eq!(stack.axis("gameplay", "turn"), 0.0);
+        assert!(input.is_mouse_motion_consumed());
+    }
+}
@@ -0,0 +1,665 @@
+//! Gamepad input
+//!
+//! Gamepad state is fed by a [`GamepadBackend`], which can wrap a platform
+//! gamepad library or, for tests and replays, [`SyntheticGamepadBackend`].
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use glam::Vec2;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Identifies a connected gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub usize);
+
+/// Digital gamepad buttons, named by position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    /// Bottom face button (A on Xbox, Cross on PlayStation)
+    South,
+    /// Right face button (B on Xbox, Circle on PlayStation)
+    East,
+    /// Top face button (Y on Xbox, Triangle on PlayStation)
+    North,
+    /// Left face button (X on Xbox, Square on PlayStation)
+    West,
+    LeftBumper,
+    RightBumper,
+    /// Left stick click
+    LeftStick,
+    /// Right stick click
+    RightStick,
+    Select,
+    Start,
+    /// Home/guide button
+    Guide,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Analog gamepad axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    /// Positive is up
+    LeftStickY,
+    RightStickX,
+    /// Positive is up
+    RightStickY,
+    /// 0.0 (released) to 1.0 (fully pressed)
+    LeftTrigger,
+    /// 0.0 (released) to 1.0 (fully pressed)
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    const COUNT: usize = 6;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Raw gamepad event produced by a backend
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad was connected
+    Connected { id: GamepadId, name: String },
+    /// A gamepad was disconnected
+    Disconnected { id: GamepadId },
+    /// A button changed state
+    Button {
+        id: GamepadId,
+        button: GamepadButton,
+        pressed: bool,
+    },
+    /// An axis moved; sticks are -1.0..=1.0 and triggers 0.0..=1.0
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Force feedback request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rumble {
+    /// Low-frequency motor strength (0.0 to 1.0)
+    pub strong: f32,
+    /// High-frequency motor strength (0.0 to 1.0)
+    pub weak: f32,
+    /// How long to rumble
+    pub duration: Duration,
+}
+
+impl Rumble {
+    /// Create a rumble request
+    #[must_use]
+    pub fn new(strong: f32, weak: f32, duration: Duration) -> Self {
+        Self {
+            strong: strong.clamp(0.0, 1.0),
+            weak: weak.clamp(0.0, 1.0),
+            duration,
+        }
+    }
+
+    /// Stop any active rumble
+    #[must_use]
+    pub fn stop() -> Self {
+        Self::new(0.0, 0.0, Duration::ZERO)
+    }
+}
+
+/// Source of gamepad events
+pub trait GamepadBackend: Send {
+    /// Append all events since the last poll
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>);
+
+    /// Start or stop rumble on a gamepad
+    ///
+    /// Backends without force feedback can ignore this.
+    fn rumble(&mut self, _id: GamepadId, _rumble: Rumble) {}
+}
+
+/// Shared state of a [`SyntheticGamepadBackend`]
+#[derive(Debug, Default)]
+struct SyntheticState {
+    events: Vec<GamepadEvent>,
+    rumbles: Vec<(GamepadId, Rumble)>,
+}
+
+/// Backend driven by events pushed from code
+///
+/// Clones share the same queue, so a test can keep one clone to push events
+/// after handing another to [`Gamepads::set_backend`].
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticGamepadBackend {
+    state: Arc<Mutex<SyntheticState>>,
+}
+
+impl SyntheticGamepadBackend {
+    /// Create an empty backend
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event for the next poll
+    pub fn push(&self, event: GamepadEvent) {
+        self.lock().events.push(event);
+    }
+
+    /// Take the rumble requests received so far
+    pub fn take_rumbles(&self) -> Vec<(GamepadId, Rumble)> {
+        std::mem::take(&mut self.lock().rumbles)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SyntheticState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl GamepadBackend for SyntheticGamepadBackend {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.append(&mut self.lock().events);
+    }
+
+    fn rumble(&mut self, id: GamepadId, rumble: Rumble) {
+        self.lock().rumbles.push((id, rumble));
+    }
+}
+
+/// Shape applied to analog input after the deadzone
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Finer control near the center
+    Quadratic,
+    Cubic,
+    /// Custom exponent
+    Power(f32),
+}
+
+impl ResponseCurve {
+    /// Apply the curve to a value in 0.0..=1.0
+    #[must_use]
+    pub fn apply(self, value: f32) -> f32 {
+        match self {
+            Self::Linear => value,
+            Self::Quadratic => value * value,
+            Self::Cubic => value * value * value,
+            Self::Power(exponent) => value.powf(exponent.max(0.0)),
+        }
+    }
+}
+
+/// Deadzone and response curve for one analog control
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AnalogSettings {
+    /// Input below this magnitude reads as zero
+    pub deadzone: f32,
+    /// Input above this magnitude reads as full deflection
+    pub outer_deadzone: f32,
+    /// Response curve applied after the deadzones
+    pub curve: ResponseCurve,
+}
+
+impl AnalogSettings {
+    /// Create settings with a deadzone and linear response
+    #[must_use]
+    pub fn new(deadzone: f32) -> Self {
+        Self {
+            deadzone,
+            outer_deadzone: 1.0,
+            curve: ResponseCurve::Linear,
+        }
+    }
+
+    /// Set the outer deadzone
+    #[must_use]
+    pub fn with_outer_deadzone(mut self, outer_deadzone: f32) -> Self {
+        self.outer_deadzone = outer_deadzone;
+        self
+    }
+
+    /// Set the response curve
+    #[must_use]
+    pub fn with_curve(mut self, curve: ResponseCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Map a raw magnitude to the configured response
+    #[must_use]
+    pub fn apply(&self, magnitude: f32) -> f32 {
+        let range = self.outer_deadzone - self.deadzone;
+        if magnitude <= self.deadzone || range <= 0.0 {
+            return 0.0;
+        }
+        self.curve
+            .apply(((magnitude - self.deadzone) / range).clamp(0.0, 1.0))
+    }
+
+    /// Apply the settings radially to a stick vector
+    #[must_use]
+    pub fn apply_stick(&self, raw: Vec2) -> Vec2 {
+        let magnitude = raw.length();
+        if magnitude <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        raw / magnitude * self.apply(magnitude)
+    }
+}
+
+/// Analog settings shared by all gamepads
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GamepadSettings {
+    pub left_stick: AnalogSettings,
+    pub right_stick: AnalogSettings,
+    pub triggers: AnalogSettings,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            left_stick: AnalogSettings::new(0.15).with_outer_deadzone(0.95),
+            right_stick: AnalogSettings::new(0.15).with_outer_deadzone(0.95),
+            triggers: AnalogSettings::new(0.05),
+        }
+    }
+}
+
+/// State of one connected gamepad
+#[derive(Debug, Clone, Default)]
+pub struct Gamepad {
+    name: String,
+    pressed: HashSet<GamepadButton>,
+    just_pressed: HashSet<GamepadButton>,
+    just_released: HashSet<GamepadButton>,
+    /// Raw axis values indexed by [`GamepadAxis`]
+    axes: [f32; GamepadAxis::COUNT],
+    settings: GamepadSettings,
+}
+
+impl Gamepad {
+    /// Get the device name reported by the backend
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check if a button is held
+    #[must_use]
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// Check if a button was pressed this frame
+    #[must_use]
+    pub fn is_just_pressed(&self, button: GamepadButton) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    /// Check if a button was released this frame
+    #[must_use]
+    pub fn is_just_released(&self, button: GamepadButton) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    /// Iterate over buttons pressed this frame
+    pub fn just_pressed_buttons(&self) -> impl Iterator<Item = GamepadButton> + '_ {
+        self.just_pressed.iter().copied()
+    }
+
+    /// Get an axis value without deadzone or curve
+    #[must_use]
+    pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis.index()]
+    }
+
+    /// Get an axis value with deadzone and curve applied
+    ///
+    /// Stick axes are processed as part of their stick, so a stick pushed
+    /// diagonally does not lose its X component to the deadzone.
+    #[must_use]
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        match axis {
+            GamepadAxis::LeftStickX => self.left_stick().x,
+            GamepadAxis::LeftStickY => self.left_stick().y,
+            GamepadAxis::RightStickX => self.right_stick().x,
+            GamepadAxis::RightStickY => self.right_stick().y,
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => {
+                self.settings.triggers.apply(self.raw_axis(axis))
+            }
+        }
+    }
+
+    /// Get the left stick with deadzone and curve applied
+    #[must_use]
+    pub fn left_stick(&self) -> Vec2 {
+        self.settings.left_stick.apply_stick(Vec2::new(
+            self.raw_axis(GamepadAxis::LeftStickX),
+            self.raw_axis(GamepadAxis::LeftStickY),
+        ))
+    }
+
+    /// Get the right stick with deadzone and curve applied
+    #[must_use]
+    pub fn right_stick(&self) -> Vec2 {
+        self.settings.right_stick.apply_stick(Vec2::new(
+            self.raw_axis(GamepadAxis::RightStickX),
+            self.raw_axis(GamepadAxis::RightStickY),
+        ))
+    }
+
+    /// Get the left trigger with deadzone and curve applied
+    #[must_use]
+    pub fn left_trigger(&self) -> f32 {
+        self.axis(GamepadAxis::LeftTrigger)
+    }
+
+    /// Get the right trigger with deadzone and curve applied
+    #[must_use]
+    pub fn right_trigger(&self) -> f32 {
+        self.axis(GamepadAxis::RightTrigger)
+    }
+}
+
+/// All connected gamepads
+#[derive(Default)]
+pub struct Gamepads {
+    pads: FxHashMap<GamepadId, Gamepad>,
+    just_connected: Vec<GamepadId>,
+    just_disconnected: Vec<GamepadId>,
+    settings: GamepadSettings,
+    /// Rumble requests waiting for the next poll
+    pending_rumble: Vec<(GamepadId, Rumble)>,
+    backend: Option<Box<dyn GamepadBackend>>,
+    /// Scratch buffer for polled events
+    events: Vec<GamepadEvent>,
+}
+
+impl Gamepads {
+    /// Create an empty gamepad set without a backend
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the backend polled by [`Gamepads::poll`]
+    pub fn set_backend(&mut self, backend: impl GamepadBackend + 'static) {
+        self.backend = Some(Box::new(backend));
+    }
+
+    /// Check if a backend is installed
+    #[must_use]
+    pub fn has_backend(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// Read events from the backend and send pending rumble requests
+    pub fn poll(&mut self) {
+        let Some(backend) = self.backend.as_mut() else {
+            return;
+        };
+        for (id, rumble) in self.pending_rumble.drain(..) {
+            backend.rumble(id, rumble);
+        }
+
+        let mut events = std::mem::take(&mut self.events);
+        backend.poll(&mut events);
+        for event in events.drain(..) {
+            self.process_event(event);
+        }
+        self.events = events;
+    }
+
+    /// Apply a single event
+    pub fn process_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected { id, name } => {
+                if !self.pads.contains_key(&id) {
+                    self.just_connected.push(id);
+                }
+                let pad = self.pads.entry(id).or_default();
+                pad.name = name;
+                pad.settings = self.settings;
+            }
+            GamepadEvent::Disconnected { id } => {
+                if self.pads.remove(&id).is_some() {
+                    self.just_disconnected.push(id);
+                }
+            }
+            GamepadEvent::Button {
+                id,
+                button,
+                pressed,
+            } => {
+                let Some(pad) = self.pads.get_mut(&id) else {
+                    return;
+                };
+                if pressed {
+                    if pad.pressed.insert(button) {
+                        pad.just_pressed.insert(button);
+                    }
+                } else if pad.pressed.remove(&button) {
+                    pad.just_released.insert(button);
+                }
+            }
+            GamepadEvent::Axis { id, axis, value } => {
+                if let Some(pad) = self.pads.get_mut(&id) {
+                    pad.axes[axis.index()] = value;
+                }
+            }
+        }
+    }
+
+    /// Clear per-frame state
+    pub fn update(&mut self) {
+        self.just_connected.clear();
+        self.just_disconnected.clear();
+        for pad in self.pads.values_mut() {
+            pad.just_pressed.clear();
+            pad.just_released.clear();
+        }
+    }
+
+    /// Get a connected gamepad
+    #[must_use]
+    pub fn get(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.pads.get(&id)
+    }
+
+    /// Check if a gamepad is connected
+    #[must_use]
+    pub fn is_connected(&self, id: GamepadId) -> bool {
+        self.pads.contains_key(&id)
+    }
+
+    /// Iterate over connected gamepads in ID order
+    pub fn iter(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        let mut ids: Vec<GamepadId> = self.pads.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| (id, &self.pads[&id]))
+    }
+
+    /// Get the lowest-numbered connected gamepad
+    #[must_use]
+    pub fn first(&self) -> Option<(GamepadId, &Gamepad)> {
+        self.iter().next()
+    }
+
+    /// Get the number of connected gamepads
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pads.len()
+    }
+
+    /// Check if no gamepads are connected
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pads.is_empty()
+    }
+
+    /// Gamepads connected this frame
+    #[must_use]
+    pub fn just_connected(&self) -> &[GamepadId] {
+        &self.just_connected
+    }
+
+    /// Gamepads disconnected this frame
+    #[must_use]
+    pub fn just_disconnected(&self) -> &[GamepadId] {
+        &self.just_disconnected
+    }
+
+    /// Check if any gamepad holds a button
+    #[must_use]
+    pub fn any_pressed(&self, button: GamepadButton) -> bool {
+        self.pads.values().any(|pad| pad.is_pressed(button))
+    }
+
+    /// Check if any gamepad pressed a button this frame
+    #[must_use]
+    pub fn any_just_pressed(&self, button: GamepadButton) -> bool {
+        self.pads.values().any(|pad| pad.is_just_pressed(button))
+    }
+
+    /// Check if any gamepad released a button this frame
+    #[must_use]
+    pub fn any_just_released(&self, button: GamepadButton) -> bool {
+        self.pads.values().any(|pad| pad.is_just_released(button))
+    }
+
+    /// Get the processed axis value with the largest magnitude across gamepads
+    #[must_use]
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.pads
+            .values()
+            .map(|pad| pad.axis(axis))
+            .fold(0.0, |best, v| if v.abs() > best.abs() { v } else { best })
+    }
+
+    /// Get the analog settings
+    #[must_use]
+    pub fn settings(&self) -> &GamepadSettings {
+        &self.settings
+    }
+
+    /// Set the analog settings for all gamepads
+    pub fn set_settings(&mut self, settings: GamepadSettings) {
+        self.settings = settings;
+        for pad in self.pads.values_mut() {
+            pad.settings = settings;
+        }
+    }
+
+    /// Request rumble, sent to the backend on the next poll
+    pub fn rumble(&mut self, id: GamepadId, rumble: Rumble) {
+        if self.is_connected(id) {
+            self.pending_rumble.push((id, rumble));
+        }
+    }
+}
+
+impl std::fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gamepads")
+            .field("pads", &self.pads)
+            .field("settings", &self.settings)
+            .field("has_backend", &self.backend.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAD: GamepadId = GamepadId(0);
+
+    fn connected() -> (Gamepads, SyntheticGamepadBackend) {
+        let backend = SyntheticGamepadBackend::new();
+        let mut gamepads = Gamepads::new();
+        gamepads.set_backend(backend.clone());
+        backend.push(GamepadEvent::Connected {
+            id: PAD,
+            name: "Test Pad".into(),
+        });
+        gamepads.poll();
+        (gamepads, backend)
+    }
+
+    #[test]
+    fn test_connection_and_buttons() {
+        let (mut gamepads, backend) = connected();
+        assert_eq!(gamepads.just_connected(), &[PAD]);
+        assert_eq!(gamepads.get(PAD).unwrap().name(), "Test Pad");
+
+        backend.push(GamepadEvent::Button {
+            id: PAD,
+            button: GamepadButton::South,
+            pressed: true,
+        });
+        gamepads.update();
+        gamepads.poll();
+        assert!(gamepads.just_connected().is_empty());
+        assert!(gamepads.any_just_pressed(GamepadButton::South));
+
+        gamepads.update();
+        assert!(gamepads.any_pressed(GamepadButton::South));
+        assert!(!gamepads.any_just_pressed(GamepadButton::South));
+
+        backend.push(GamepadEvent::Disconnected { id: PAD });
+        gamepads.poll();
+        assert_eq!(gamepads.just_disconnected(), &[PAD]);
+        assert!(gamepads.is_empty());
+        assert!(!gamepads.any_pressed(GamepadButton::South));
+    }
+
+    #[test]
+    fn test_deadzone_and_curve() {
+        let (mut gamepads, _) = connected();
+        let axis = |axis, value| GamepadEvent::Axis {
+            id: PAD,
+            axis,
+            value,
+        };
+
+        gamepads.process_event(axis(GamepadAxis::LeftStickX, 0.1));
+        assert_eq!(gamepads.get(PAD).unwrap().left_stick(), Vec2::ZERO);
+        assert_eq!(
+            gamepads.get(PAD).unwrap().raw_axis(GamepadAxis::LeftStickX),
+            0.1
+        );
+
+        gamepads.process_event(axis(GamepadAxis::LeftStickX, 1.0));
+        assert!((gamepads.get(PAD).unwrap().left_stick().x - 1.0).abs() < 0.001);
+
+        gamepads.set_settings(GamepadSettings {
+            triggers: AnalogSettings::new(0.0).with_curve(ResponseCurve::Quadratic),
+            ..GamepadSettings::default()
+        });
+        gamepads.process_event(axis(GamepadAxis::RightTrigger, 0.5));
+        assert!((gamepads.axis(GamepadAxis::RightTrigger) - 0.25).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_rumble_is_sent_on_poll() {
+        let (mut gamepads, backend) = connected();
+        let rumble = Rumble::new(1.0, 0.5, Duration::from_millis(200));
+
+        gamepads.rumble(PAD, rumble);
+        gamepads.rumble(GamepadId(7), rumble);
+        assert!(backend.take_rumbles().is_empty());
+
+        gamepads.poll();
+        assert_eq!(backend.take_rumbles(), vec![(PAD, rumble)]);
+    }
+}
@@ -0,0 +1,395 @@
+//! Input history for buffering, combos and chords
+//!
+//! [`InputHistory`] records timestamped press and release events for the
+//! actions of an [`ActionMap`] so gameplay code can ask about recent input
+//! rather than only the current frame.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rustc_hash::FxHashMap;
+
+use super::action::ActionMap;
+use super::state::Input;
+
+/// Default time events are kept for
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(2);
+
+/// Default maximum number of stored events
+const DEFAULT_CAPACITY: usize = 128;
+
+/// Whether an action was pressed or released
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEventKind {
+    Press,
+    Release,
+}
+
+/// A timestamped action event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputRecord {
+    /// Action name
+    pub action: String,
+    /// Press or release
+    pub kind: InputEventKind,
+    /// Time the event was recorded
+    pub time: Duration,
+}
+
+/// An ordered sequence of action presses with timing limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combo {
+    /// Actions to press, in order
+    pub steps: Vec<String>,
+    /// Maximum time between consecutive steps
+    pub max_gap: Duration,
+    /// Maximum time from the first to the last step
+    pub max_total: Option<Duration>,
+}
+
+impl Combo {
+    /// Create a combo with a 300 ms gap between steps
+    #[must_use]
+    pub fn new<S: Into<String>>(steps: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            steps: steps.into_iter().map(Into::into).collect(),
+            max_gap: Duration::from_millis(300),
+            max_total: None,
+        }
+    }
+
+    /// Set the maximum time between consecutive steps
+    #[must_use]
+    pub fn with_max_gap(mut self, max_gap: Duration) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Set the maximum time for the whole combo
+    #[must_use]
+    pub fn with_max_total(mut self, max_total: Duration) -> Self {
+        self.max_total = Some(max_total);
+        self
+    }
+}
+
+/// Timestamped history of action presses and releases
+///
+/// Call [`InputHistory::update`] once per frame after input events have been
+/// processed; the engine does this for [`EngineContext`](crate::core::EngineContext).
+/// Recognizers such as [`InputHistory::matches`] and
+/// [`InputHistory::double_tapped`] report true only on the frame the input
+/// completes.
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    events: VecDeque<InputRecord>,
+    /// Press time of actions currently held
+    held: FxHashMap<String, Duration>,
+    /// Time of the current frame
+    now: Duration,
+    /// Time of the previous frame
+    previous: Duration,
+    /// Events older than this are dropped
+    max_age: Duration,
+    /// Maximum number of stored events
+    capacity: usize,
+}
+
+impl InputHistory {
+    /// Create an empty history
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            held: FxHashMap::default(),
+            now: Duration::ZERO,
+            previous: Duration::ZERO,
+            max_age: DEFAULT_MAX_AGE,
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Set how long events are kept
+    #[must_use]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Set the maximum number of stored events
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Advance to `now` and record this frame's action presses and releases
+    pub fn update(&mut self, actions: &ActionMap, input: &Input, now: Duration) {
+        self.advance(now);
+        for action in actions.actions() {
+            if actions.just_pressed(input, action) {
+                self.record(action, InputEventKind::Press);
+            }
+            if actions.just_released(input, action) {
+                self.record(action, InputEventKind::Release);
+            }
+        }
+    }
+
+    /// Start a new frame at `now` and drop expired events
+    pub fn advance(&mut self, now: Duration) {
+        self.previous = self.now;
+        self.now = now;
+
+        while let Some(front) = self.events.front()
+            && now.saturating_sub(front.time) > self.max_age
+        {
+            self.events.pop_front();
+        }
+    }
+
+    /// Record an event at the current time
+    pub fn record(&mut self, action: impl Into<String>, kind: InputEventKind) {
+        let action = action.into();
+        match kind {
+            InputEventKind::Press => {
+                self.held.insert(action.clone(), self.now);
+            }
+            InputEventKind::Release => {
+                self.held.remove(&action);
+            }
+        }
+
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(InputRecord {
+            action,
+            kind,
+            time: self.now,
+        });
+    }
+
+    /// Iterate over stored events, oldest first
+    pub fn events(&self) -> impl Iterator<Item = &InputRecord> {
+        self.events.iter()
+    }
+
+    /// Forget all events and held actions
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.held.clear();
+    }
+
+    /// Get the time of the most recent press of an action
+    #[must_use]
+    pub fn last_press(&self, action: &str) -> Option<Duration> {
+        self.presses(action).next().map(|(_, record)| record.time)
+    }
+
+    /// Check if an action was pressed within `window` of now
+    #[must_use]
+    pub fn pressed_within(&self, action: &str, window: Duration) -> bool {
+        self.last_press(action)
+            .is_some_and(|time| self.now.saturating_sub(time) <= window)
+    }
+
+    /// Check if an action was released within `window` of now
+    #[must_use]
+    pub fn released_within(&self, action: &str, window: Duration) -> bool {
+        self.events.iter().rev().any(|record| {
+            record.kind == InputEventKind::Release
+                && record.action == action
+                && self.now.saturating_sub(record.time) <= window
+        })
+    }
+
+    /// Consume a buffered press made within `window` of now
+    ///
+    /// Returns true and removes the press so it only triggers once, e.g. an
+    /// attack pressed shortly before landing.
+    pub fn consume_press(&mut self, action: &str, window: Duration) -> bool {
+        let Some((index, record)) = self.presses(action).next() else {
+            return false;
+        };
+        if self.now.saturating_sub(record.time) > window {
+            return false;
+        }
+        self.events.remove(index);
+        true
+    }
+
+    /// Get how long an action has been held, if it is held
+    #[must_use]
+    pub fn held_for(&self, action: &str) -> Option<Duration> {
+        self.held
+            .get(action)
+            .map(|&pressed| self.now.saturating_sub(pressed))
+    }
+
+    /// Check if a combo was completed this frame
+    #[must_use]
+    pub fn matches(&self, combo: &Combo) -> bool {
+        let Some((last, steps)) = combo.steps.split_last() else {
+            return false;
+        };
+        let Some((mut index, record)) = self.presses(last).next() else {
+            return false;
+        };
+        if record.time != self.now {
+            return false;
+        }
+
+        let end = record.time;
+        let mut next_time = end;
+        for step in steps.iter().rev() {
+            let found = self
+                .events
+                .range(..index)
+                .enumerate()
+                .rev()
+                .find(|(_, r)| r.kind == InputEventKind::Press && r.action == *step);
+            let Some((i, record)) = found else {
+                return false;
+            };
+            if next_time.saturating_sub(record.time) > combo.max_gap {
+                return false;
+            }
+            index = i;
+            next_time = record.time;
+        }
+
+        combo
+            .max_total
+            .is_none_or(|max| end.saturating_sub(next_time) <= max)
+    }
+
+    /// Check if all actions became held together this frame
+    ///
+    /// The actions must all be held and pressed within `window` of each other,
+    /// with the last one pressed this frame.
+    #[must_use]
+    pub fn chord(&self, actions: &[&str], window: Duration) -> bool {
+        let mut first = Duration::MAX;
+        let mut last = Duration::ZERO;
+        for action in actions {
+            let Some(&pressed) = self.held.get(*action) else {
+                return false;
+            };
+            first = first.min(pressed);
+            last = last.max(pressed);
+        }
+        !actions.is_empty() && last == self.now && last - first <= window
+    }
+
+    /// Check if an action was pressed twice within `max_interval`, completing this frame
+    #[must_use]
+    pub fn double_tapped(&self, action: &str, max_interval: Duration) -> bool {
+        let mut presses = self.presses(action);
+        match (presses.next(), presses.next()) {
+            (Some((_, second)), Some((_, first))) => {
+                second.time == self.now && second.time - first.time <= max_interval
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if an action has been held for `threshold`, reported on the frame it crosses
+    #[must_use]
+    pub fn long_pressed(&self, action: &str, threshold: Duration) -> bool {
+        self.held.get(action).is_some_and(|&pressed| {
+            let fires_at = pressed + threshold;
+            fires_at <= self.now && fires_at > self.previous
+        })
+    }
+
+    /// Presses of an action, newest first, with their event index
+    fn presses<'a>(&'a self, action: &'a str) -> impl Iterator<Item = (usize, &'a InputRecord)> {
+        self.events
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(move |(_, r)| r.kind == InputEventKind::Press && r.action == action)
+    }
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn press_at(history: &mut InputHistory, millis: u64, actions: &[&str]) {
+        history.advance(ms(millis));
+        for action in actions {
+            history.record(*action, InputEventKind::Press);
+        }
+    }
+
+    #[test]
+    fn test_buffered_press() {
+        let mut history = InputHistory::new();
+        press_at(&mut history, 1000, &["attack"]);
+        history.advance(ms(1100));
+
+        assert!(history.pressed_within("attack", ms(150)));
+        assert!(!history.pressed_within("attack", ms(50)));
+        assert!(history.consume_press("attack", ms(150)));
+        assert!(!history.consume_press("attack", ms(150)));
+
+        history.advance(ms(4000));
+        assert_eq!(history.events().count(), 0);
+    }
+
+    #[test]
+    fn test_combo_and_chord() {
+        let combo = Combo::new(["down", "forward", "punch"]).with_max_gap(ms(200));
+        let mut history = InputHistory::new();
+
+        press_at(&mut history, 0, &["down"]);
+        press_at(&mut history, 150, &["forward"]);
+        press_at(&mut history, 300, &["punch"]);
+        assert!(history.matches(&combo));
+        history.advance(ms(316));
+        assert!(!history.matches(&combo));
+
+        press_at(&mut history, 1000, &["down"]);
+        press_at(&mut history, 1300, &["forward"]);
+        press_at(&mut history, 1400, &["punch"]);
+        assert!(!history.matches(&combo));
+
+        press_at(&mut history, 2000, &["block"]);
+        press_at(&mut history, 2030, &["jump"]);
+        assert!(history.chord(&["block", "jump"], ms(50)));
+        assert!(!history.chord(&["block", "jump"], ms(10)));
+        history.record("block", InputEventKind::Release);
+        assert!(!history.chord(&["block", "jump"], ms(50)));
+    }
+
+    #[test]
+    fn test_double_tap_and_long_press() {
+        let mut history = InputHistory::new();
+        press_at(&mut history, 0, &["dash"]);
+        history.advance(ms(50));
+        history.record("dash", InputEventKind::Release);
+        press_at(&mut history, 200, &["dash"]);
+        assert!(history.double_tapped("dash", ms(250)));
+        assert!(!history.double_tapped("dash", ms(100)));
+
+        history.advance(ms(500));
+        assert!(!history.long_pressed("dash", ms(500)));
+        history.advance(ms(716));
+        assert!(history.long_pressed("dash", ms(500)));
+        history.advance(ms(733));
+        assert!(!history.long_pressed("dash", ms(500)));
+        assert_eq!(history.held_for("dash"), Some(ms(533)));
+    }
+}
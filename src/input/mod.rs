@@ -1,5 +1,22 @@
 //! Input handling module
 
+mod action;
+mod context;
+mod gamepad;
+mod history;
+mod mouse;
 mod state;
+mod text;
+mod touch;
 
+pub use action::{ActionMap, ActionMapError, Axis2dBinding, AxisBinding, Binding, BindingConflict};
+pub use context::{InputContext, InputContextStack};
+pub use gamepad::{
+    AnalogSettings, Gamepad, GamepadAxis, GamepadBackend, GamepadButton, GamepadEvent, GamepadId,
+    GamepadSettings, Gamepads, ResponseCurve, Rumble, SyntheticGamepadBackend,
+};
+pub use history::{Combo, InputEventKind, InputHistory, InputRecord};
+pub use mouse::MouseSettings;
 pub use state::Input;
+pub use text::{ImePreedit, ImeRequest, TextInput};
+pub use touch::{Gesture, GestureSettings, TouchPhase, TouchPoint, Touches};
@@ -0,0 +1,115 @@
+//! Mouse look settings
+//!
+//! Sensitivity and smoothing applied to raw mouse motion for camera control.
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Sensitivity and smoothing for raw mouse motion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MouseSettings {
+    /// Multiplier applied to raw motion
+    pub sensitivity: f32,
+    /// Flip vertical motion
+    pub invert_y: bool,
+    /// Fraction of the previous motion kept after 1/60 s (0.0 disables smoothing)
+    pub smoothing: f32,
+}
+
+impl Default for MouseSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.0,
+            invert_y: false,
+            smoothing: 0.0,
+        }
+    }
+}
+
+impl MouseSettings {
+    /// Set the sensitivity
+    #[must_use]
+    pub fn with_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    /// Set whether vertical motion is inverted
+    #[must_use]
+    pub fn with_invert_y(mut self, invert_y: bool) -> Self {
+        self.invert_y = invert_y;
+        self
+    }
+
+    /// Set the smoothing factor, clamped to 0.0..0.99
+    #[must_use]
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 0.99);
+        self
+    }
+
+    /// Scale raw motion and blend it with the previous frame's result
+    ///
+    /// Smoothing is scaled by the frame time `dt` in seconds, so it feels the
+    /// same at any framerate.
+    #[must_use]
+    pub fn apply(&self, raw: Vec2, previous: Vec2, dt: f32) -> Vec2 {
+        let mut scaled = raw * self.sensitivity;
+        if self.invert_y {
+            scaled.y = -scaled.y;
+        }
+        let keep = self.smoothing.clamp(0.0, 0.99).powf(dt.max(0.0) * 60.0);
+        scaled.lerp(previous, keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+
+    #[test]
+    fn test_sensitivity_and_invert() {
+        let settings = MouseSettings::default()
+            .with_sensitivity(2.0)
+            .with_invert_y(true);
+        assert_eq!(
+            settings.apply(Vec2::new(1.0, 3.0), Vec2::ZERO, 1.0 / 60.0),
+            Vec2::new(2.0, -6.0)
+        );
+    }
+
+    #[test]
+    fn test_smoothed_look_delta() {
+        let mut input = Input::new();
+        input.set_mouse_settings(MouseSettings::default().with_smoothing(0.5));
+        input.set_frame_delta(1.0 / 60.0);
+
+        input.process_raw_mouse_motion(Vec2::new(4.0, 0.0));
+        input.process_raw_mouse_motion(Vec2::new(4.0, 0.0));
+        assert_eq!(input.raw_mouse_delta(), Vec2::new(8.0, 0.0));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.mouse_look_delta(), Vec2::new(4.0, 0.0));
+
+        // Motion stops, smoothing eases out
+        input.update();
+        assert_eq!(input.raw_mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.mouse_look_delta(), Vec2::new(2.0, 0.0));
+        input.update();
+        assert_eq!(input.mouse_look_delta(), Vec2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_smoothing_is_framerate_independent() {
+        let settings = MouseSettings::default().with_smoothing(2.0);
+        assert_eq!(settings.smoothing, 0.99);
+
+        // Two 120 Hz frames decay as much as one 60 Hz frame
+        let settings = MouseSettings::default().with_smoothing(0.5);
+        let previous = Vec2::new(8.0, 0.0);
+        let once = settings.apply(Vec2::ZERO, previous, 1.0 / 60.0);
+        let half = settings.apply(Vec2::ZERO, previous, 1.0 / 120.0);
+        let twice = settings.apply(Vec2::ZERO, half, 1.0 / 120.0);
+        assert!((once - twice).length() < 1e-4);
+    }
+}
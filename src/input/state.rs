@@ -5,6 +5,11 @@ use std::collections::HashSet;
 use winit::event::{ElementState, MouseButton};
 use winit::keyboard::KeyCode;
 
+use super::gamepad::{GamepadAxis, GamepadButton, Gamepads};
+use super::mouse::MouseSettings;
+use super::text::TextInput;
+use super::touch::{TouchPhase, Touches};
+
 /// Input state manager
 #[derive(Debug)]
 pub struct Input {
@@ -14,6 +19,8 @@ pub struct Input {
     just_pressed_keys: HashSet<KeyCode>,
     /// Keys that were just released this frame
     just_released_keys: HashSet<KeyCode>,
+    /// Keys that auto-repeated this frame
+    repeated_keys: HashSet<KeyCode>,
     /// Currently pressed mouse buttons
     pressed_mouse_buttons: HashSet<MouseButton>,
     /// Mouse buttons just pressed this frame
@@ -22,10 +29,46 @@ pub struct Input {
     just_released_mouse_buttons: HashSet<MouseButton>,
     /// Current mouse position
     mouse_position: Vec2,
-    /// Mouse movement delta this frame
+    /// Cursor movement delta this frame
     mouse_delta: Vec2,
+    /// Raw device motion this frame, unaffected by cursor position or screen edges
+    raw_mouse_delta: Vec2,
+    /// Smoothed look delta from the previous frame
+    previous_look_delta: Vec2,
+    /// Length of the current frame in seconds, for mouse smoothing
+    frame_delta: f32,
+    /// Sensitivity and smoothing for mouse look
+    mouse_settings: MouseSettings,
+    /// Whether the cursor is hidden and locked for mouse look
+    relative_mouse_mode: bool,
+    /// Relative mouse mode change not yet applied to the window
+    cursor_dirty: bool,
+    /// Whether the window has focus
+    focused: bool,
     /// Scroll wheel delta this frame
     scroll_delta: Vec2,
+    /// Connected gamepads
+    gamepads: Gamepads,
+    /// Typed text and IME state
+    text: TextInput,
+    /// Touch points and gestures
+    touches: Touches,
+    /// Drive the mouse from the primary touch
+    touch_emulates_mouse: bool,
+    /// Keys consumed for the rest of this frame
+    consumed_keys: HashSet<KeyCode>,
+    /// Mouse buttons consumed for the rest of this frame
+    consumed_mouse_buttons: HashSet<MouseButton>,
+    /// Gamepad buttons consumed for the rest of this frame
+    consumed_gamepad_buttons: HashSet<GamepadButton>,
+    /// Gamepad axes consumed for the rest of this frame
+    consumed_gamepad_axes: HashSet<GamepadAxis>,
+    /// Whether the scroll wheel was consumed this frame
+    scroll_consumed: bool,
+    /// Whether mouse motion was consumed this frame
+    mouse_motion_consumed: bool,
+    /// Whether all input was consumed this frame
+    all_consumed: bool,
 }
 
 impl Input {
@@ -35,12 +78,31 @@ impl Input {
             pressed_keys: HashSet::new(),
             just_pressed_keys: HashSet::new(),
             just_released_keys: HashSet::new(),
+            repeated_keys: HashSet::new(),
             pressed_mouse_buttons: HashSet::new(),
             just_pressed_mouse_buttons: HashSet::new(),
             just_released_mouse_buttons: HashSet::new(),
             mouse_position: Vec2::ZERO,
             mouse_delta: Vec2::ZERO,
+            raw_mouse_delta: Vec2::ZERO,
+            previous_look_delta: Vec2::ZERO,
+            frame_delta: 1.0 / 60.0,
+            mouse_settings: MouseSettings::default(),
+            relative_mouse_mode: false,
+            cursor_dirty: false,
+            focused: true,
             scroll_delta: Vec2::ZERO,
+            gamepads: Gamepads::new(),
+            text: TextInput::new(),
+            touches: Touches::new(),
+            touch_emulates_mouse: false,
+            consumed_keys: HashSet::new(),
+            consumed_mouse_buttons: HashSet::new(),
+            consumed_gamepad_buttons: HashSet::new(),
+            consumed_gamepad_axes: HashSet::new(),
+            scroll_consumed: false,
+            mouse_motion_consumed: false,
+            all_consumed: false,
         }
     }
 
@@ -48,10 +110,23 @@ impl Input {
     pub fn update(&mut self) {
         self.just_pressed_keys.clear();
         self.just_released_keys.clear();
+        self.repeated_keys.clear();
         self.just_pressed_mouse_buttons.clear();
         self.just_released_mouse_buttons.clear();
+        self.previous_look_delta = self.look_delta();
         self.mouse_delta = Vec2::ZERO;
+        self.raw_mouse_delta = Vec2::ZERO;
         self.scroll_delta = Vec2::ZERO;
+        self.gamepads.update();
+        self.text.update();
+        self.touches.update();
+        self.consumed_keys.clear();
+        self.consumed_mouse_buttons.clear();
+        self.consumed_gamepad_buttons.clear();
+        self.consumed_gamepad_axes.clear();
+        self.scroll_consumed = false;
+        self.mouse_motion_consumed = false;
+        self.all_consumed = false;
     }
 
     /// Process a keyboard event
@@ -70,6 +145,11 @@ impl Input {
         }
     }
 
+    /// Process an auto-repeat of a held key
+    pub fn process_key_repeat(&mut self, key_code: KeyCode) {
+        self.repeated_keys.insert(key_code);
+    }
+
     /// Process a mouse button event
     pub fn process_mouse_button(&mut self, button: MouseButton, state: ElementState) {
         match state {
@@ -88,13 +168,73 @@ impl Input {
 
     /// Process mouse movement
     pub fn process_mouse_motion(&mut self, position: Vec2) {
-        self.mouse_delta = position - self.mouse_position;
+        self.mouse_delta += position - self.mouse_position;
         self.mouse_position = position;
     }
 
     /// Process raw mouse delta (for first-person camera)
+    ///
+    /// Adds to both [`Input::mouse_delta`] and [`Input::raw_mouse_delta`].
     pub fn process_mouse_delta(&mut self, delta: Vec2) {
         self.mouse_delta += delta;
+        self.raw_mouse_delta += delta;
+    }
+
+    /// Process raw device motion without moving [`Input::mouse_delta`]
+    ///
+    /// The engine routes device motion here, so the cursor delta isn't
+    /// counted twice.
+    pub fn process_raw_mouse_motion(&mut self, delta: Vec2) {
+        self.raw_mouse_delta += delta;
+    }
+
+    /// Set the length of the current frame in seconds, for mouse smoothing
+    pub fn set_frame_delta(&mut self, dt: f32) {
+        self.frame_delta = dt;
+    }
+
+    /// Process a touch event
+    ///
+    /// With mouse emulation enabled, the primary touch also moves the mouse
+    /// and presses the left button.
+    pub fn process_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: Vec2,
+        pressure: Option<f32>,
+    ) {
+        let emulate = self.touch_emulates_mouse
+            && match phase {
+                TouchPhase::Started => self.touches.is_empty(),
+                _ => self.touches.primary() == Some(id),
+            };
+
+        self.touches.process_touch(id, phase, position, pressure);
+
+        if emulate {
+            self.process_mouse_motion(position);
+            match phase {
+                TouchPhase::Started => {
+                    self.process_mouse_button(MouseButton::Left, ElementState::Pressed);
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    self.process_mouse_button(MouseButton::Left, ElementState::Released);
+                }
+                TouchPhase::Moved => {}
+            }
+        }
+    }
+
+    /// Process window focus changes
+    ///
+    /// Relative mouse mode is reapplied when focus returns, since platforms
+    /// release the cursor grab when the window loses focus.
+    pub fn process_focus(&mut self, focused: bool) {
+        self.focused = focused;
+        if focused && self.relative_mouse_mode {
+            self.cursor_dirty = true;
+        }
     }
 
     /// Process scroll wheel
@@ -102,34 +242,130 @@ impl Input {
         self.scroll_delta += delta;
     }
 
+    /// Consume a key so it reads as released for the rest of this frame
+    ///
+    /// Use when a higher layer such as the UI has handled the key and lower
+    /// layers such as gameplay should not react to it.
+    pub fn consume_key(&mut self, key: KeyCode) {
+        self.consumed_keys.insert(key);
+    }
+
+    /// Consume a mouse button so it reads as released for the rest of this frame
+    pub fn consume_mouse_button(&mut self, button: MouseButton) {
+        self.consumed_mouse_buttons.insert(button);
+    }
+
+    /// Consume a gamepad button for action bindings for the rest of this frame
+    ///
+    /// Direct queries through [`Input::gamepads`] are not affected.
+    pub fn consume_gamepad_button(&mut self, button: GamepadButton) {
+        self.consumed_gamepad_buttons.insert(button);
+    }
+
+    /// Consume a gamepad axis for action bindings for the rest of this frame
+    ///
+    /// Direct queries through [`Input::gamepads`] are not affected.
+    pub fn consume_gamepad_axis(&mut self, axis: GamepadAxis) {
+        self.consumed_gamepad_axes.insert(axis);
+    }
+
+    /// Consume the scroll wheel so it reads as still for the rest of this frame
+    pub fn consume_scroll(&mut self) {
+        self.scroll_consumed = true;
+    }
+
+    /// Consume mouse motion so all mouse deltas read as zero for the rest of this frame
+    pub fn consume_mouse_motion(&mut self) {
+        self.mouse_motion_consumed = true;
+    }
+
+    /// Consume all keys, buttons, axes, mouse motion and scrolling for this frame
+    pub fn consume_all(&mut self) {
+        self.all_consumed = true;
+    }
+
+    /// Check if a key was consumed this frame
+    pub fn is_key_consumed(&self, key: KeyCode) -> bool {
+        self.all_consumed || self.consumed_keys.contains(&key)
+    }
+
+    /// Check if a mouse button was consumed this frame
+    pub fn is_mouse_button_consumed(&self, button: MouseButton) -> bool {
+        self.all_consumed || self.consumed_mouse_buttons.contains(&button)
+    }
+
+    /// Check if a gamepad button was consumed this frame
+    pub fn is_gamepad_button_consumed(&self, button: GamepadButton) -> bool {
+        self.all_consumed || self.consumed_gamepad_buttons.contains(&button)
+    }
+
+    /// Check if a gamepad axis was consumed this frame
+    pub fn is_gamepad_axis_consumed(&self, axis: GamepadAxis) -> bool {
+        self.all_consumed || self.consumed_gamepad_axes.contains(&axis)
+    }
+
+    /// Check if the scroll wheel was consumed this frame
+    pub fn is_scroll_consumed(&self) -> bool {
+        self.all_consumed || self.scroll_consumed
+    }
+
+    /// Check if mouse motion was consumed this frame
+    pub fn is_mouse_motion_consumed(&self) -> bool {
+        self.all_consumed || self.mouse_motion_consumed
+    }
+
     /// Check if a key is currently pressed
     pub fn is_key_pressed(&self, key: KeyCode) -> bool {
-        self.pressed_keys.contains(&key)
+        self.pressed_keys.contains(&key) && !self.is_key_consumed(key)
     }
 
     /// Check if a key was just pressed this frame
     pub fn is_key_just_pressed(&self, key: KeyCode) -> bool {
-        self.just_pressed_keys.contains(&key)
+        self.just_pressed_keys.contains(&key) && !self.is_key_consumed(key)
+    }
+
+    /// Check if a key was pressed or auto-repeated this frame
+    ///
+    /// Use for text editing keys such as backspace and arrows.
+    pub fn is_key_pressed_or_repeated(&self, key: KeyCode) -> bool {
+        (self.just_pressed_keys.contains(&key) || self.repeated_keys.contains(&key))
+            && !self.is_key_consumed(key)
     }
 
     /// Check if a key was just released this frame
     pub fn is_key_just_released(&self, key: KeyCode) -> bool {
-        self.just_released_keys.contains(&key)
+        self.just_released_keys.contains(&key) && !self.is_key_consumed(key)
     }
 
     /// Check if a mouse button is currently pressed
     pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
-        self.pressed_mouse_buttons.contains(&button)
+        self.pressed_mouse_buttons.contains(&button) && !self.is_mouse_button_consumed(button)
     }
 
     /// Check if a mouse button was just pressed this frame
     pub fn is_mouse_button_just_pressed(&self, button: MouseButton) -> bool {
-        self.just_pressed_mouse_buttons.contains(&button)
+        self.just_pressed_mouse_buttons.contains(&button) && !self.is_mouse_button_consumed(button)
     }
 
     /// Check if a mouse button was just released this frame
     pub fn is_mouse_button_just_released(&self, button: MouseButton) -> bool {
-        self.just_released_mouse_buttons.contains(&button)
+        self.just_released_mouse_buttons.contains(&button) && !self.is_mouse_button_consumed(button)
+    }
+
+    /// Iterate over keys that were just pressed this frame
+    pub fn just_pressed_keys(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.just_pressed_keys
+            .iter()
+            .copied()
+            .filter(|&key| !self.is_key_consumed(key))
+    }
+
+    /// Iterate over mouse buttons that were just pressed this frame
+    pub fn just_pressed_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.just_pressed_mouse_buttons
+            .iter()
+            .copied()
+            .filter(|&button| !self.is_mouse_button_consumed(button))
     }
 
     /// Get current mouse position
@@ -138,13 +374,138 @@ impl Input {
     }
 
     /// Get mouse movement delta this frame
+    ///
+    /// This is cursor motion plus anything passed to
+    /// [`Input::process_mouse_delta`], without sensitivity or smoothing. Use
+    /// [`Input::mouse_look_delta`] for camera control.
     pub fn mouse_delta(&self) -> Vec2 {
-        self.mouse_delta
+        if self.is_mouse_motion_consumed() {
+            Vec2::ZERO
+        } else {
+            self.mouse_delta
+        }
+    }
+
+    /// Get raw device motion this frame, without sensitivity or smoothing
+    pub fn raw_mouse_delta(&self) -> Vec2 {
+        if self.is_mouse_motion_consumed() {
+            Vec2::ZERO
+        } else {
+            self.raw_mouse_delta
+        }
+    }
+
+    /// Get raw device motion with sensitivity and smoothing applied
+    pub fn mouse_look_delta(&self) -> Vec2 {
+        if self.is_mouse_motion_consumed() {
+            Vec2::ZERO
+        } else {
+            self.look_delta()
+        }
+    }
+
+    /// Smoothed look delta, ignoring consumption so smoothing stays continuous
+    fn look_delta(&self) -> Vec2 {
+        self.mouse_settings.apply(
+            self.raw_mouse_delta,
+            self.previous_look_delta,
+            self.frame_delta,
+        )
+    }
+
+    /// Get the mouse look settings
+    pub fn mouse_settings(&self) -> &MouseSettings {
+        &self.mouse_settings
+    }
+
+    /// Set the mouse look settings
+    pub fn set_mouse_settings(&mut self, settings: MouseSettings) {
+        self.mouse_settings = settings;
+    }
+
+    /// Hide and lock the cursor for mouse look, or release it
+    ///
+    /// Falls back to confining the cursor on platforms that can't lock it.
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        if self.relative_mouse_mode != enabled {
+            self.relative_mouse_mode = enabled;
+            self.cursor_dirty = true;
+        }
+    }
+
+    /// Check if relative mouse mode is enabled
+    pub fn is_relative_mouse_mode(&self) -> bool {
+        self.relative_mouse_mode
+    }
+
+    /// Take a pending relative mouse mode change to apply to the window
+    pub fn take_cursor_request(&mut self) -> Option<bool> {
+        std::mem::take(&mut self.cursor_dirty).then_some(self.relative_mouse_mode)
+    }
+
+    /// Check if the window has focus
+    pub fn is_focused(&self) -> bool {
+        self.focused
     }
 
     /// Get scroll wheel delta this frame
     pub fn scroll_delta(&self) -> Vec2 {
-        self.scroll_delta
+        if self.is_scroll_consumed() {
+            Vec2::ZERO
+        } else {
+            self.scroll_delta
+        }
+    }
+
+    /// Get a processed gamepad axis across all gamepads, or zero if consumed
+    pub fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        if self.is_gamepad_axis_consumed(axis) {
+            0.0
+        } else {
+            self.gamepads.axis(axis)
+        }
+    }
+
+    /// Get typed text and IME state
+    pub fn text_input(&self) -> &TextInput {
+        &self.text
+    }
+
+    /// Get typed text and IME state mutably, e.g. to enable IME
+    pub fn text_input_mut(&mut self) -> &mut TextInput {
+        &mut self.text
+    }
+
+    /// Get touch points and gestures
+    pub fn touches(&self) -> &Touches {
+        &self.touches
+    }
+
+    /// Get touch points and gestures mutably, e.g. to change gesture settings
+    pub fn touches_mut(&mut self) -> &mut Touches {
+        &mut self.touches
+    }
+
+    /// Drive the mouse position and left button from the primary touch
+    ///
+    /// Lets mouse-driven code such as `ui::Widget` handling work on touchscreens.
+    pub fn set_touch_emulates_mouse(&mut self, enabled: bool) {
+        self.touch_emulates_mouse = enabled;
+    }
+
+    /// Check if touch mouse emulation is enabled
+    pub fn touch_emulates_mouse(&self) -> bool {
+        self.touch_emulates_mouse
+    }
+
+    /// Get the connected gamepads
+    pub fn gamepads(&self) -> &Gamepads {
+        &self.gamepads
+    }
+
+    /// Get the connected gamepads mutably, e.g. to install a backend or rumble
+    pub fn gamepads_mut(&mut self) -> &mut Gamepads {
+        &mut self.gamepads
     }
 }
 
@@ -0,0 +1,194 @@
+//! Text input and IME
+//!
+//! Collects committed text and IME composition events for text fields, and
+//! queues IME requests that the engine applies to the window.
+
+use glam::Vec2;
+use winit::event::Ime;
+
+/// A request to change the window's IME state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImeRequest {
+    /// Allow or disallow IME input
+    SetAllowed(bool),
+    /// Place the candidate window near this area, in physical pixels
+    SetCursorArea { position: Vec2, size: Vec2 },
+}
+
+/// Current IME composition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImePreedit {
+    /// Text being composed
+    pub text: String,
+    /// Byte range of the cursor or selection within `text`
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// Per-frame text input state
+#[derive(Debug, Default)]
+pub struct TextInput {
+    /// Text committed this frame, from typing and IME commits
+    text: String,
+    /// IME events received this frame
+    ime_events: Vec<Ime>,
+    /// Composition in progress
+    preedit: Option<ImePreedit>,
+    /// Whether the platform IME is active
+    ime_enabled: bool,
+    /// Whether IME input has been allowed
+    ime_allowed: bool,
+    /// Requests not yet applied to the window
+    requests: Vec<ImeRequest>,
+}
+
+impl TextInput {
+    /// Create an empty text input state
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clear per-frame state
+    pub fn update(&mut self) {
+        self.text.clear();
+        self.ime_events.clear();
+    }
+
+    /// Process text produced by a key press, including key repeats
+    ///
+    /// Control characters such as backspace and enter are dropped; handle
+    /// those keys through [`Input`](super::Input) instead.
+    pub fn process_text(&mut self, text: &str) {
+        self.text.extend(text.chars().filter(|c| !c.is_control()));
+    }
+
+    /// Process an IME event from the window
+    pub fn process_ime(&mut self, ime: Ime) {
+        match &ime {
+            Ime::Enabled => self.ime_enabled = true,
+            Ime::Preedit(text, _) if text.is_empty() => self.preedit = None,
+            Ime::Preedit(text, cursor) => {
+                self.preedit = Some(ImePreedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                });
+            }
+            Ime::Commit(text) => {
+                self.preedit = None;
+                self.process_text(text);
+            }
+            Ime::Disabled => {
+                self.ime_enabled = false;
+                self.preedit = None;
+            }
+        }
+        self.ime_events.push(ime);
+    }
+
+    /// Get the text committed this frame
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Iterate over the characters committed this frame
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.text.chars()
+    }
+
+    /// Get the IME events received this frame
+    #[must_use]
+    pub fn ime_events(&self) -> &[Ime] {
+        &self.ime_events
+    }
+
+    /// Get the composition in progress, if any
+    #[must_use]
+    pub fn preedit(&self) -> Option<&ImePreedit> {
+        self.preedit.as_ref()
+    }
+
+    /// Check if the platform IME is active
+    #[must_use]
+    pub fn is_ime_enabled(&self) -> bool {
+        self.ime_enabled
+    }
+
+    /// Check if IME input has been allowed
+    #[must_use]
+    pub fn is_ime_allowed(&self) -> bool {
+        self.ime_allowed
+    }
+
+    /// Allow or disallow IME input, e.g. when a text field gains or loses focus
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        if self.ime_allowed != allowed {
+            self.ime_allowed = allowed;
+            self.requests.push(ImeRequest::SetAllowed(allowed));
+        }
+        if !allowed {
+            self.preedit = None;
+        }
+    }
+
+    /// Position the IME candidate window next to a text cursor, in physical pixels
+    pub fn set_ime_cursor_area(&mut self, position: Vec2, size: Vec2) {
+        self.requests
+            .retain(|r| !matches!(r, ImeRequest::SetCursorArea { .. }));
+        self.requests
+            .push(ImeRequest::SetCursorArea { position, size });
+    }
+
+    /// Take the requests that still have to be applied to the window
+    pub fn drain_requests(&mut self) -> Vec<ImeRequest> {
+        std::mem::take(&mut self.requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_and_ime_commit() {
+        let mut text = TextInput::new();
+        text.process_text("h");
+        text.process_text("\u{8}");
+        text.process_text("i");
+
+        text.process_ime(Ime::Enabled);
+        text.process_ime(Ime::Preedit("にほ".into(), Some((6, 6))));
+        assert_eq!(text.preedit().unwrap().text, "にほ");
+
+        text.process_ime(Ime::Commit("日本".into()));
+        assert!(text.preedit().is_none());
+        assert_eq!(text.text(), "hi日本");
+        assert_eq!(text.ime_events().len(), 3);
+
+        text.update();
+        assert!(text.text().is_empty());
+        assert!(text.ime_events().is_empty());
+        assert!(text.is_ime_enabled());
+    }
+
+    #[test]
+    fn test_ime_requests() {
+        let mut text = TextInput::new();
+        text.set_ime_allowed(true);
+        text.set_ime_allowed(true);
+        text.set_ime_cursor_area(Vec2::ZERO, Vec2::ONE);
+        text.set_ime_cursor_area(Vec2::new(10.0, 20.0), Vec2::new(1.0, 16.0));
+
+        assert_eq!(
+            text.drain_requests(),
+            vec![
+                ImeRequest::SetAllowed(true),
+                ImeRequest::SetCursorArea {
+                    position: Vec2::new(10.0, 20.0),
+                    size: Vec2::new(1.0, 16.0),
+                },
+            ]
+        );
+        assert!(text.drain_requests().is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+//! Touch input and gestures
+//!
+//! Tracks active touch points and recognizes taps, swipes, pinches and
+//! rotations from them.
+
+use std::time::Duration;
+
+use glam::Vec2;
+use rustc_hash::FxHashMap;
+pub use winit::event::TouchPhase;
+
+/// A finger on the touch surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchPoint {
+    /// Identifier, unique while the finger is down
+    pub id: u64,
+    /// Latest phase
+    pub phase: TouchPhase,
+    /// Current position in physical pixels
+    pub position: Vec2,
+    /// Position when the touch started
+    pub start_position: Vec2,
+    /// Normalized pressure (0.0 to 1.0) if the device reports it
+    pub pressure: Option<f32>,
+    /// Time the touch started
+    pub start_time: Duration,
+    /// Whether another finger was down at any point during this touch
+    multi: bool,
+}
+
+impl TouchPoint {
+    /// Get the movement since the touch started
+    #[must_use]
+    pub fn distance(&self) -> Vec2 {
+        self.position - self.start_position
+    }
+}
+
+/// A recognized gesture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// Short touch without movement
+    Tap { position: Vec2 },
+    /// Quick single-finger stroke
+    Swipe {
+        start: Vec2,
+        end: Vec2,
+        /// Pixels per second
+        velocity: Vec2,
+    },
+    /// Two fingers moving apart or together
+    Pinch {
+        center: Vec2,
+        /// Ratio of the finger distance to last frame's (above 1.0 is zooming in)
+        scale: f32,
+    },
+    /// Two fingers turning around their center
+    Rotate {
+        center: Vec2,
+        /// Radians since last frame, counter-clockwise on screen
+        angle: f32,
+    },
+}
+
+/// Thresholds for gesture recognition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+    /// Longest touch that still counts as a tap
+    pub tap_max_duration: Duration,
+    /// Furthest a tap may move, in pixels
+    pub tap_max_distance: f32,
+    /// Shortest stroke that counts as a swipe, in pixels
+    pub swipe_min_distance: f32,
+    /// Longest stroke that counts as a swipe
+    pub swipe_max_duration: Duration,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_max_duration: Duration::from_millis(250),
+            tap_max_distance: 10.0,
+            swipe_min_distance: 50.0,
+            swipe_max_duration: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Touch points and gestures
+#[derive(Debug, Clone, Default)]
+pub struct Touches {
+    /// Fingers currently down
+    active: FxHashMap<u64, TouchPoint>,
+    /// Touches started this frame
+    just_started: Vec<u64>,
+    /// Touches ended or cancelled this frame
+    just_ended: Vec<TouchPoint>,
+    /// Gestures recognized this frame
+    gestures: Vec<Gesture>,
+    /// Two-finger positions at the start of the frame
+    pair: Option<(u64, u64, Vec2, Vec2)>,
+    /// Touch driving mouse emulation
+    primary: Option<u64>,
+    /// Current time
+    now: Duration,
+    settings: GestureSettings,
+}
+
+impl Touches {
+    /// Create an empty touch state
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process a touch event
+    pub fn process_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: Vec2,
+        pressure: Option<f32>,
+    ) {
+        match phase {
+            TouchPhase::Started => {
+                let multi = !self.active.is_empty();
+                for point in self.active.values_mut() {
+                    point.multi |= multi;
+                }
+                if self.active.is_empty() {
+                    self.primary = Some(id);
+                }
+                self.active.insert(
+                    id,
+                    TouchPoint {
+                        id,
+                        phase,
+                        position,
+                        start_position: position,
+                        pressure,
+                        start_time: self.now,
+                        multi,
+                    },
+                );
+                self.just_started.push(id);
+            }
+            TouchPhase::Moved => {
+                if let Some(point) = self.active.get_mut(&id) {
+                    point.phase = phase;
+                    point.position = position;
+                    point.pressure = pressure.or(point.pressure);
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let Some(mut point) = self.active.remove(&id) else {
+                    return;
+                };
+                point.phase = phase;
+                point.position = position;
+                if phase == TouchPhase::Ended {
+                    self.recognize_release(&point);
+                }
+                self.just_ended.push(point);
+                if self.primary == Some(id) {
+                    self.primary = None;
+                }
+            }
+        }
+    }
+
+    /// Start a new frame at `now` and recognize two-finger gestures
+    pub fn advance(&mut self, now: Duration) {
+        self.now = now;
+
+        let mut fingers: Vec<&TouchPoint> = self.active.values().collect();
+        if fingers.len() != 2 {
+            self.pair = None;
+            return;
+        }
+        fingers.sort_by_key(|p| p.id);
+        let (a, b) = (fingers[0], fingers[1]);
+
+        if let Some((id_a, id_b, old_a, old_b)) = self.pair
+            && (id_a, id_b) == (a.id, b.id)
+        {
+            let center = (a.position + b.position) * 0.5;
+            let old_span = old_b - old_a;
+            let span = b.position - a.position;
+
+            let old_length = old_span.length();
+            if old_length > f32::EPSILON {
+                let scale = span.length() / old_length;
+                if (scale - 1.0).abs() > 1e-4 {
+                    self.gestures.push(Gesture::Pinch { center, scale });
+                }
+            }
+
+            // Screen Y points down, so flip it for a counter-clockwise angle
+            let angle = -old_span.angle_to(span);
+            if angle.is_finite() && angle.abs() > 1e-4 {
+                self.gestures.push(Gesture::Rotate { center, angle });
+            }
+        }
+        self.pair = Some((a.id, b.id, a.position, b.position));
+    }
+
+    /// Clear per-frame state
+    pub fn update(&mut self) {
+        self.just_started.clear();
+        self.just_ended.clear();
+        self.gestures.clear();
+    }
+
+    /// Get an active touch
+    #[must_use]
+    pub fn get(&self, id: u64) -> Option<&TouchPoint> {
+        self.active.get(&id)
+    }
+
+    /// Iterate over active touches
+    pub fn iter(&self) -> impl Iterator<Item = &TouchPoint> {
+        self.active.values()
+    }
+
+    /// Get the number of fingers down
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Check if no fingers are down
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Touches started this frame
+    #[must_use]
+    pub fn just_started(&self) -> &[u64] {
+        &self.just_started
+    }
+
+    /// Touches ended or cancelled this frame, with their final state
+    #[must_use]
+    pub fn just_ended(&self) -> &[TouchPoint] {
+        &self.just_ended
+    }
+
+    /// Gestures recognized this frame
+    #[must_use]
+    pub fn gestures(&self) -> &[Gesture] {
+        &self.gestures
+    }
+
+    /// The first finger of the current touch sequence
+    #[must_use]
+    pub fn primary(&self) -> Option<u64> {
+        self.primary
+    }
+
+    /// Get the gesture thresholds
+    #[must_use]
+    pub fn settings(&self) -> &GestureSettings {
+        &self.settings
+    }
+
+    /// Set the gesture thresholds
+    pub fn set_settings(&mut self, settings: GestureSettings) {
+        self.settings = settings;
+    }
+
+    /// Recognize single-finger gestures when a touch ends
+    fn recognize_release(&mut self, point: &TouchPoint) {
+        if point.multi {
+            return;
+        }
+        let duration = self.now.saturating_sub(point.start_time);
+        let distance = point.distance();
+
+        if duration <= self.settings.tap_max_duration
+            && distance.length() <= self.settings.tap_max_distance
+        {
+            self.gestures.push(Gesture::Tap {
+                position: point.position,
+            });
+        } else if duration <= self.settings.swipe_max_duration
+            && distance.length() >= self.settings.swipe_min_distance
+        {
+            let seconds = duration.as_secs_f32().max(1.0 / 1000.0);
+            self.gestures.push(Gesture::Swipe {
+                start: point.start_position,
+                end: point.position,
+                velocity: distance / seconds,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+    use winit::event::MouseButton;
+
+    #[test]
+    fn test_tap_and_swipe() {
+        let mut touches = Touches::new();
+        touches.process_touch(1, TouchPhase::Started, Vec2::new(100.0, 100.0), None);
+        touches.advance(Duration::from_millis(100));
+        touches.process_touch(1, TouchPhase::Ended, Vec2::new(102.0, 100.0), None);
+        assert_eq!(
+            touches.gestures(),
+            &[Gesture::Tap {
+                position: Vec2::new(102.0, 100.0)
+            }]
+        );
+
+        touches.update();
+        touches.process_touch(2, TouchPhase::Started, Vec2::ZERO, Some(0.5));
+        touches.advance(Duration::from_millis(300));
+        touches.process_touch(2, TouchPhase::Ended, Vec2::new(200.0, 0.0), None);
+        let Gesture::Swipe { velocity, .. } = touches.gestures()[0] else {
+            panic!("expected swipe");
+        };
+        assert!((velocity.x - 1000.0).abs() < 0.1);
+        assert_eq!(touches.just_ended()[0].pressure, Some(0.5));
+    }
+
+    #[test]
+    fn test_pinch_and_rotate() {
+        let mut touches = Touches::new();
+        touches.process_touch(1, TouchPhase::Started, Vec2::new(-10.0, 0.0), None);
+        touches.process_touch(2, TouchPhase::Started, Vec2::new(10.0, 0.0), None);
+        touches.advance(Duration::ZERO);
+        touches.update();
+
+        // Spread to twice the distance and turn a quarter counter-clockwise
+        touches.process_touch(1, TouchPhase::Moved, Vec2::new(0.0, 20.0), None);
+        touches.process_touch(2, TouchPhase::Moved, Vec2::new(0.0, -20.0), None);
+        touches.advance(Duration::from_millis(16));
+
+        let gestures = touches.gestures();
+        assert!(
+            gestures
+                .iter()
+                .any(|g| matches!(g, Gesture::Pinch { scale, .. } if (scale - 2.0).abs() < 0.001))
+        );
+        assert!(gestures.iter().any(|g| matches!(
+            g,
+            Gesture::Rotate { angle, .. } if (angle - std::f32::consts::FRAC_PI_2).abs() < 0.001
+        )));
+
+        // Multi-finger touches never count as taps
+        touches.update();
+        touches.process_touch(1, TouchPhase::Ended, Vec2::new(0.0, 20.0), None);
+        assert!(touches.gestures().is_empty());
+    }
+
+    #[test]
+    fn test_mouse_emulation() {
+        let mut input = Input::new();
+        input.set_touch_emulates_mouse(true);
+
+        input.process_touch(7, TouchPhase::Started, Vec2::new(5.0, 6.0), None);
+        input.process_touch(8, TouchPhase::Started, Vec2::new(50.0, 60.0), None);
+        assert!(input.is_mouse_button_just_pressed(MouseButton::Left));
+        assert_eq!(input.mouse_position(), Vec2::new(5.0, 6.0));
+
+        input.process_touch(7, TouchPhase::Ended, Vec2::new(5.0, 6.0), None);
+        assert!(input.is_mouse_button_just_released(MouseButton::Left));
+        assert_eq!(input.touches().len(), 1);
+    }
+}
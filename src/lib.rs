@@ -33,8 +33,8 @@ pub mod prelude {
     pub use crate::assets::{AssetHandle, Assets, WeakAssetHandle};
     pub use crate::core::{DebugInfo, Engine, EngineConfig, EngineContext, FrameStats, Game};
     pub use crate::ecs::{Name, Transform, Velocity, World};
-    pub use crate::input::Input;
-    pub use crate::physics::{ColliderHandle, Physics, RigidBodyHandle};
+    pub use crate::input::{ActionMap, Input, InputContext};
+    pub use crate::physics::{Collider, ColliderHandle, Physics, RigidBody, RigidBodyHandle};
     pub use crate::renderer::{Camera, Light, Material, Mesh, RenderFrame, Renderer, Vertex};
     pub use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
     pub use winit::keyboard::KeyCode;
@@ -10,6 +10,7 @@
 
 use engine::ai::{Arrive, SteeringBehavior};
 use engine::audio::AudioManager;
+use engine::hecs::Entity;
 use engine::prelude::*;
 use engine::renderer::{EmitterConfig, ParticleEmitter, UiRect};
 
@@ -27,10 +28,9 @@ struct DemoGame {
     follower_model: Option<(wgpu::Buffer, wgpu::BindGroup)>,
     ground_model: Option<(wgpu::Buffer, wgpu::BindGroup)>,
 
-    // Physics
-    physics: Physics,
-    cube_body: Option<RigidBodyHandle>,
-    follower_body: Option<RigidBodyHandle>,
+    // Physics entities
+    cube_entity: Option<Entity>,
+    follower_entity: Option<Entity>,
 
     // Particles
     emitter: Option<ParticleEmitter>,
@@ -54,9 +54,8 @@ impl DemoGame {
             cube_model: None,
             follower_model: None,
             ground_model: None,
-            physics: Physics::new(),
-            cube_body: None,
-            follower_body: None,
+            cube_entity: None,
+            follower_entity: None,
             emitter: None,
             audio: None,
             camera_yaw: 0.0,
@@ -85,25 +84,27 @@ impl Game for DemoGame {
         self.cube_mesh = Some(cube);
         self.ground_mesh = Some(ground);
 
-        // 3. Setup Physics
-        let ground_body = self.physics.create_static_body(Vec3::ZERO, Quat::IDENTITY);
-        self.physics.add_ground_plane(ground_body);
+        // 3. Setup Physics (bodies are created and synced by the engine)
+        ctx.world.spawn((
+            Transform::new(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::new(100.0, 0.1, 100.0)),
+        ));
 
         // Player cube
-        let cube_body = self
-            .physics
-            .create_dynamic_body(Vec3::new(0.0, 5.0, 0.0), Quat::IDENTITY);
-        self.physics
-            .add_box_collider(cube_body, Vec3::splat(0.5), 1.0);
-        self.cube_body = Some(cube_body);
+        self.cube_entity = Some(ctx.world.spawn((
+            Transform::from_position(Vec3::new(0.0, 5.0, 0.0)),
+            RigidBody::dynamic(),
+            Collider::cuboid(Vec3::splat(0.5)),
+        )));
 
         // Follower cube (different starting pos)
-        let follower_body = self
-            .physics
-            .create_dynamic_body(Vec3::new(5.0, 5.0, -5.0), Quat::IDENTITY);
-        self.physics
-            .add_box_collider(follower_body, Vec3::splat(0.5), 1.0);
-        self.follower_body = Some(follower_body);
+        self.follower_entity = Some(ctx.world.spawn((
+            Transform::from_position(Vec3::new(5.0, 5.0, -5.0)),
+            Velocity::default(),
+            RigidBody::dynamic(),
+            Collider::cuboid(Vec3::splat(0.5)),
+        )));
 
         // 4. Setup Particles (Smoke trail)
         let config = EmitterConfig::default()
@@ -175,15 +176,17 @@ impl Game for DemoGame {
             force.x += force_strength;
         }
 
-        if let Some(body) = self.cube_body {
+        if let Some(cube) = self.cube_entity {
             if force != Vec3::ZERO {
-                self.physics.apply_force(body, force);
+                if let Some(body) = ctx.physics.entity_body(cube) {
+                    ctx.physics.apply_force(body, force);
+                }
 
                 // Spawn particles at player position
-                if let (Some(pos), Some(emitter)) =
-                    (self.physics.get_position(body), &mut self.emitter)
+                if let (Ok(transform), Some(emitter)) =
+                    (ctx.world.get::<Transform>(cube), &mut self.emitter)
                 {
-                    emitter.set_position(pos);
+                    emitter.set_position(transform.position);
                     emitter.start();
                 }
             } else if let Some(emitter) = &mut self.emitter {
@@ -192,21 +195,26 @@ impl Game for DemoGame {
         }
 
         // AI Follower Logic
-        if let (Some(target_body), Some(follower_body)) = (self.cube_body, self.follower_body) {
-            let target_pos = self.physics.get_position(target_body).unwrap_or(Vec3::ZERO);
-            let follower_pos = self
-                .physics
-                .get_position(follower_body)
-                .unwrap_or(Vec3::ZERO);
-            let follower_vel = self
-                .physics
-                .get_linear_velocity(follower_body)
-                .unwrap_or(Vec3::ZERO);
+        if let (Some(target), Some(follower)) = (self.cube_entity, self.follower_entity)
+            && let Some(follower_body) = ctx.physics.entity_body(follower)
+        {
+            let target_pos = ctx
+                .world
+                .get::<Transform>(target)
+                .map_or(Vec3::ZERO, |t| t.position);
+            let follower_pos = ctx
+                .world
+                .get::<Transform>(follower)
+                .map_or(Vec3::ZERO, |t| t.position);
+            let follower_vel = ctx
+                .world
+                .get::<Velocity>(follower)
+                .map_or(Vec3::ZERO, |v| v.linear);
 
             let arrive = Arrive::new(target_pos, 10.0, 5.0);
             let steering = arrive.calculate(follower_pos, follower_vel);
 
-            self.physics
+            ctx.physics
                 .apply_force(follower_body, steering.linear * 5.0);
         }
 
@@ -215,9 +223,6 @@ impl Game for DemoGame {
             self.show_ui = !self.show_ui;
         }
 
-        // Physics step
-        self.physics.step(dt);
-
         // Update particle state
         if let Some(emitter) = &mut self.emitter {
             emitter.update(dt);
@@ -225,23 +230,18 @@ impl Game for DemoGame {
         }
 
         // Update model transforms
-        if let (Some(body), Some((buffer, _))) = (self.cube_body, &self.cube_model)
-            && let (Some(pos), Some(rot)) = (
-                self.physics.get_position(body),
-                self.physics.get_rotation(body),
-            )
-        {
-            ctx.renderer()
-                .update_model_buffer(buffer, Mat4::from_rotation_translation(rot, pos));
-        }
-        if let (Some(body), Some((buffer, _))) = (self.follower_body, &self.follower_model)
-            && let (Some(pos), Some(rot)) = (
-                self.physics.get_position(body),
-                self.physics.get_rotation(body),
-            )
-        {
-            ctx.renderer()
-                .update_model_buffer(buffer, Mat4::from_rotation_translation(rot, pos));
+        for (entity, model) in [
+            (self.cube_entity, &self.cube_model),
+            (self.follower_entity, &self.follower_model),
+        ] {
+            if let (Some(entity), Some((buffer, _))) = (entity, model)
+                && let Ok(transform) = ctx.world.get::<Transform>(entity)
+            {
+                ctx.renderer().update_model_buffer(
+                    buffer,
+                    Mat4::from_rotation_translation(transform.rotation, transform.position),
+                );
+            }
         }
     }
 
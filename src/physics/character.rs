@@ -0,0 +1,389 @@
+//! Kinematic character controller
+//!
+//! [`Physics::move_character`] moves a kinematic body by a desired
+//! translation, sliding along walls, climbing slopes and steps, and snapping
+//! to the ground, and reports what the character touched on the way.
+
+use glam::Vec3;
+use hecs::Entity;
+use rapier3d::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
+use rapier3d::na::{Unit, Vector3};
+use rapier3d::prelude::QueryFilter;
+
+use super::sync::user_data_to_entity;
+use super::world::{ColliderHandle, Physics, RigidBodyHandle};
+use crate::ecs::{Transform, World};
+
+/// Stepping over small obstacles such as stairs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Autostep {
+    /// Maximum height of a step
+    pub max_height: f32,
+    /// Minimum free width needed on top of a step
+    pub min_width: f32,
+    /// Also step onto dynamic bodies
+    pub include_dynamic_bodies: bool,
+}
+
+/// Character controller settings
+///
+/// Lengths are absolute, in world units.
+///
+/// ```ignore
+/// let controller = CharacterController::new()
+///     .with_max_slope(50f32.to_radians())
+///     .with_autostep(0.5, 0.2)
+///     .with_snap_to_ground(0.2);
+/// if let Some(movement) = physics.move_character(player, &controller, velocity * dt, dt)
+///     && movement.grounded
+/// {
+///     // allow jumping
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharacterController {
+    /// Up direction, used to tell floors from walls
+    pub up: Vec3,
+    /// Gap kept between the character and its surroundings
+    pub offset: f32,
+    /// Slide along obstacles instead of stopping
+    pub slide: bool,
+    /// Steepest slope the character can walk up, in radians
+    pub max_slope_climb_angle: f32,
+    /// Slope at which the character starts sliding down, in radians
+    pub min_slope_slide_angle: f32,
+    /// Step over small obstacles
+    pub autostep: Option<Autostep>,
+    /// Stick to the ground when it is at most this far below
+    pub snap_to_ground: Option<f32>,
+    /// Push dynamic bodies the character runs into
+    pub push_dynamic_bodies: bool,
+    /// Mass used when pushing dynamic bodies
+    pub mass: f32,
+}
+
+impl CharacterController {
+    /// Create a controller with 45 degree slopes and ground snapping
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            up: Vec3::Y,
+            offset: 0.01,
+            slide: true,
+            max_slope_climb_angle: std::f32::consts::FRAC_PI_4,
+            min_slope_slide_angle: std::f32::consts::FRAC_PI_4,
+            autostep: None,
+            snap_to_ground: Some(0.2),
+            push_dynamic_bodies: true,
+            mass: 80.0,
+        }
+    }
+
+    /// Set the up direction
+    #[must_use]
+    pub fn with_up(mut self, up: Vec3) -> Self {
+        self.up = up;
+        self
+    }
+
+    /// Set the gap kept between the character and its surroundings
+    #[must_use]
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set whether the character slides along obstacles
+    #[must_use]
+    pub fn with_slide(mut self, slide: bool) -> Self {
+        self.slide = slide;
+        self
+    }
+
+    /// Set the steepest walkable slope; steeper slopes also make the character slide down
+    #[must_use]
+    pub fn with_max_slope(mut self, angle: f32) -> Self {
+        self.max_slope_climb_angle = angle;
+        self.min_slope_slide_angle = angle;
+        self
+    }
+
+    /// Step over obstacles up to `max_height` with at least `min_width` of room on top
+    #[must_use]
+    pub fn with_autostep(mut self, max_height: f32, min_width: f32) -> Self {
+        self.autostep = Some(Autostep {
+            max_height,
+            min_width,
+            include_dynamic_bodies: false,
+        });
+        self
+    }
+
+    /// Snap to ground up to `distance` below, or `None` to disable
+    #[must_use]
+    pub fn with_snap_to_ground(mut self, distance: impl Into<Option<f32>>) -> Self {
+        self.snap_to_ground = distance.into();
+        self
+    }
+
+    /// Set whether and how hard the character pushes dynamic bodies
+    #[must_use]
+    pub fn with_pushing(mut self, enabled: bool, mass: f32) -> Self {
+        self.push_dynamic_bodies = enabled;
+        self.mass = mass;
+        self
+    }
+
+    /// Build the rapier controller
+    fn build(&self) -> KinematicCharacterController {
+        let up = self.up.try_normalize().unwrap_or(Vec3::Y);
+        KinematicCharacterController {
+            up: Unit::new_unchecked(Vector3::new(up.x, up.y, up.z)),
+            offset: CharacterLength::Absolute(self.offset),
+            slide: self.slide,
+            autostep: self.autostep.map(|step| CharacterAutostep {
+                max_height: CharacterLength::Absolute(step.max_height),
+                min_width: CharacterLength::Absolute(step.min_width),
+                include_dynamic_bodies: step.include_dynamic_bodies,
+            }),
+            max_slope_climb_angle: self.max_slope_climb_angle,
+            min_slope_slide_angle: self.min_slope_slide_angle,
+            snap_to_ground: self.snap_to_ground.map(CharacterLength::Absolute),
+            ..KinematicCharacterController::default()
+        }
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Something the character ran into while moving
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharacterCollision {
+    /// Collider that was hit
+    pub collider: ColliderHandle,
+    /// Entity of the collider, for colliders created from components
+    pub entity: Option<Entity>,
+    /// World-space contact point on the collider
+    pub point: Vec3,
+    /// World-space surface normal of the collider at the contact
+    pub normal: Vec3,
+    /// Translation already applied when the hit happened
+    pub translation_applied: Vec3,
+    /// Translation still to be applied when the hit happened
+    pub translation_remaining: Vec3,
+}
+
+/// Result of moving a character
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CharacterMovement {
+    /// Translation actually applied
+    pub translation: Vec3,
+    /// Whether the character is standing on the ground after moving
+    pub grounded: bool,
+    /// Whether the character is sliding down a slope that is too steep
+    pub sliding_down_slope: bool,
+    /// Obstacles hit while moving
+    pub collisions: Vec<CharacterCollision>,
+}
+
+fn to_vec3(v: &Vector3<f32>) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+impl Physics {
+    /// Move a kinematic body by `desired` over `dt` seconds
+    ///
+    /// The body's first collider is used as the character shape, and the body
+    /// is moved on the next [`Physics::step`]. Returns `None` if the body
+    /// doesn't exist or has no collider.
+    pub fn move_character(
+        &mut self,
+        body: RigidBodyHandle,
+        controller: &CharacterController,
+        desired: Vec3,
+        dt: f32,
+    ) -> Option<CharacterMovement> {
+        let rb = self.rigid_body_set.get(body.0)?;
+        let collider = self.collider_set.get(*rb.colliders().first()?)?;
+        let shape = collider.shared_shape().clone();
+        let shape_pos = *collider.position();
+        let filter = QueryFilter::default().exclude_rigid_body(body.0);
+        let rapier_controller = controller.build();
+
+        let mut collisions = Vec::new();
+        let movement = rapier_controller.move_shape(
+            dt,
+            &self.rigid_body_set,
+            &self.collider_set,
+            &self.query_pipeline,
+            shape.as_ref(),
+            &shape_pos,
+            Vector3::new(desired.x, desired.y, desired.z),
+            filter,
+            |collision| collisions.push(collision),
+        );
+
+        if controller.push_dynamic_bodies {
+            rapier_controller.solve_character_collision_impulses(
+                dt,
+                &mut self.rigid_body_set,
+                &self.collider_set,
+                &self.query_pipeline,
+                shape.as_ref(),
+                controller.mass,
+                &collisions,
+                filter,
+            );
+        }
+
+        let rb = self.rigid_body_set.get_mut(body.0)?;
+        let target = rb.translation() + movement.translation;
+        rb.set_next_kinematic_translation(target);
+
+        let collisions = collisions
+            .iter()
+            .map(|collision| CharacterCollision {
+                collider: ColliderHandle(collision.handle),
+                entity: self
+                    .collider_set
+                    .get(collision.handle)
+                    .and_then(|c| user_data_to_entity(c.user_data)),
+                point: Vec3::new(
+                    collision.hit.witness1.x,
+                    collision.hit.witness1.y,
+                    collision.hit.witness1.z,
+                ),
+                normal: to_vec3(&collision.hit.normal1),
+                translation_applied: to_vec3(&collision.translation_applied),
+                translation_remaining: to_vec3(&collision.translation_remaining),
+            })
+            .collect();
+
+        Some(CharacterMovement {
+            translation: to_vec3(&movement.translation),
+            grounded: movement.grounded,
+            sliding_down_slope: movement.is_sliding_down_slope,
+            collisions,
+        })
+    }
+
+    /// Move a kinematic entity and its [`Transform`] by `desired` over `dt` seconds
+    ///
+    /// The entity needs a kinematic [`RigidBody`](super::RigidBody) and a
+    /// [`Collider`](super::Collider) that have already been synced. Returns
+    /// `None` otherwise.
+    pub fn move_character_entity(
+        &mut self,
+        world: &mut World,
+        entity: Entity,
+        controller: &CharacterController,
+        desired: Vec3,
+        dt: f32,
+    ) -> Option<CharacterMovement> {
+        let body = self.entity_body(entity)?;
+        let movement = self.move_character(body, controller, desired, dt)?;
+        if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+            transform.position += movement.translation;
+        }
+        Some(movement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Quat;
+
+    fn level() -> Physics {
+        let mut physics = Physics::new();
+        let ground = physics.create_static_body(Vec3::ZERO, Quat::IDENTITY);
+        physics.add_box_collider(ground, Vec3::new(20.0, 0.5, 20.0), 1.0);
+        let wall = physics.create_static_body(Vec3::new(3.0, 2.0, 0.0), Quat::IDENTITY);
+        physics.add_box_collider(wall, Vec3::new(0.5, 1.5, 5.0), 1.0);
+        let step = physics.create_static_body(Vec3::new(0.0, 0.7, 3.0), Quat::IDENTITY);
+        physics.add_box_collider(step, Vec3::new(5.0, 0.2, 1.0), 1.0);
+        physics
+    }
+
+    fn walk(
+        physics: &mut Physics,
+        body: RigidBodyHandle,
+        controller: &CharacterController,
+        velocity: Vec3,
+        steps: usize,
+    ) -> CharacterMovement {
+        let dt = 1.0 / 60.0;
+        let mut movement = CharacterMovement::default();
+        for _ in 0..steps {
+            movement = physics
+                .move_character(body, controller, velocity * dt, dt)
+                .unwrap();
+            physics.step(dt);
+        }
+        movement
+    }
+
+    #[test]
+    fn test_walks_and_stops_at_wall() {
+        let mut physics = level();
+        let player = physics.create_kinematic_body(Vec3::new(0.0, 2.0, 0.0), Quat::IDENTITY);
+        physics.add_capsule_collider(player, 0.5, 0.5, 1.0);
+        physics.step(1.0 / 60.0);
+
+        let controller = CharacterController::new();
+        let falling = walk(&mut physics, player, &controller, Vec3::NEG_Y, 60);
+        assert!(falling.grounded);
+
+        let movement = walk(
+            &mut physics,
+            player,
+            &controller,
+            Vec3::new(4.0, -1.0, 0.0),
+            60,
+        );
+        let position = physics.get_position(player).unwrap();
+        assert!(position.x < 2.0 && position.x > 1.8, "x = {}", position.x);
+        assert!(movement.grounded);
+        assert!(
+            movement
+                .collisions
+                .iter()
+                .any(|c| c.normal.x < -0.9 && c.point.x < 2.6)
+        );
+    }
+
+    #[test]
+    fn test_autostep_climbs_stairs() {
+        let mut physics = level();
+        let player = physics.create_kinematic_body(Vec3::new(0.0, 2.0, 0.0), Quat::IDENTITY);
+        physics.add_capsule_collider(player, 0.5, 0.5, 1.0);
+        physics.step(1.0 / 60.0);
+
+        let blocked = CharacterController::new().with_snap_to_ground(None);
+        walk(
+            &mut physics,
+            player,
+            &blocked,
+            Vec3::new(0.0, -1.0, 3.0),
+            60,
+        );
+        assert!(physics.get_position(player).unwrap().z < 2.0);
+
+        let stepping = CharacterController::new().with_autostep(0.5, 0.2);
+        let movement = walk(
+            &mut physics,
+            player,
+            &stepping,
+            Vec3::new(0.0, -1.0, 3.0),
+            60,
+        );
+        let position = physics.get_position(player).unwrap();
+        assert!(position.z > 2.5, "z = {}", position.z);
+        assert!(position.y > 1.85, "y = {}", position.y);
+        assert!(movement.grounded);
+    }
+}
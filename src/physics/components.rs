@@ -0,0 +1,425 @@
+//! Physics components
+//!
+//! [`RigidBody`] and [`Collider`] describe the physics objects of an entity.
+//! [`Physics::step_world`](super::Physics::step_world) creates the rapier
+//! objects for them and keeps them in sync with the entity's [`Transform`](crate::ecs::Transform).
+
+use glam::{Quat, Vec3};
+use rapier3d::na::{Isometry3, Point3, Vector3};
+use rapier3d::prelude::{
+    ActiveCollisionTypes, ActiveEvents, ColliderBuilder, RigidBodyBuilder, RigidBodyType,
+    SharedShape,
+};
+use serde::{Deserialize, Serialize};
+
+use super::materials::CombineRule;
+use super::sensors::sensor_collision_types;
+use super::world::isometry;
+use crate::ecs::TypeRegistry;
+
+/// How a rigid body moves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BodyType {
+    /// Moved by the simulation; the simulation owns the transform
+    #[default]
+    Dynamic,
+    /// Never moves unless the transform is changed
+    Fixed,
+    /// Follows the transform each step and pushes dynamic bodies
+    Kinematic,
+}
+
+/// Rigid body description
+///
+/// Changes after the body has been created are not applied.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RigidBody {
+    /// How the body moves
+    pub body_type: BodyType,
+    /// Linear velocity loss per second
+    pub linear_damping: f32,
+    /// Angular velocity loss per second
+    pub angular_damping: f32,
+    /// Multiplier for world gravity
+    pub gravity_scale: f32,
+    /// Continuous collision detection for fast bodies
+    pub ccd: bool,
+    /// Prevent the body from rotating
+    pub lock_rotations: bool,
+}
+
+impl RigidBody {
+    /// Create a rigid body of the given type
+    #[must_use]
+    pub const fn new(body_type: BodyType) -> Self {
+        Self {
+            body_type,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            gravity_scale: 1.0,
+            ccd: false,
+            lock_rotations: false,
+        }
+    }
+
+    /// Create a dynamic body
+    #[must_use]
+    pub const fn dynamic() -> Self {
+        Self::new(BodyType::Dynamic)
+    }
+
+    /// Create a fixed body
+    #[must_use]
+    pub const fn fixed() -> Self {
+        Self::new(BodyType::Fixed)
+    }
+
+    /// Create a kinematic body
+    #[must_use]
+    pub const fn kinematic() -> Self {
+        Self::new(BodyType::Kinematic)
+    }
+
+    /// Set linear and angular damping
+    #[must_use]
+    pub const fn with_damping(mut self, linear: f32, angular: f32) -> Self {
+        self.linear_damping = linear;
+        self.angular_damping = angular;
+        self
+    }
+
+    /// Set the gravity multiplier
+    #[must_use]
+    pub const fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    /// Enable continuous collision detection
+    #[must_use]
+    pub const fn with_ccd(mut self, enabled: bool) -> Self {
+        self.ccd = enabled;
+        self
+    }
+
+    /// Prevent the body from rotating, e.g. for characters
+    #[must_use]
+    pub const fn with_locked_rotations(mut self) -> Self {
+        self.lock_rotations = true;
+        self
+    }
+
+    /// Create a rapier body builder from this description
+    pub(super) fn builder(&self) -> RigidBodyBuilder {
+        let body_type = match self.body_type {
+            BodyType::Dynamic => RigidBodyType::Dynamic,
+            BodyType::Fixed => RigidBodyType::Fixed,
+            BodyType::Kinematic => RigidBodyType::KinematicPositionBased,
+        };
+        let mut builder = RigidBodyBuilder::new(body_type)
+            .linear_damping(self.linear_damping)
+            .angular_damping(self.angular_damping)
+            .gravity_scale(self.gravity_scale)
+            .ccd_enabled(self.ccd);
+        if self.lock_rotations {
+            builder = builder.lock_rotations();
+        }
+        builder
+    }
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self::dynamic()
+    }
+}
+
+/// Collision shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColliderShape {
+    /// Box with the given half extents
+    Cuboid { half_extents: Vec3 },
+    /// Sphere
+    Ball { radius: f32 },
+    /// Capsule along the Y axis
+    Capsule { half_height: f32, radius: f32 },
+    /// Triangle mesh, best used for fixed bodies since it has no volume
+    TriMesh {
+        vertices: Vec<Vec3>,
+        indices: Vec<[u32; 3]>,
+    },
+    /// Convex hull of a set of points
+    ConvexHull { points: Vec<Vec3> },
+    /// Several shapes with local offsets, moving as one
+    Compound { parts: Vec<CompoundPart> },
+}
+
+/// A shape inside a [`ColliderShape::Compound`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompoundPart {
+    /// Offset from the collider origin
+    pub offset: Vec3,
+    /// Rotation relative to the collider
+    pub rotation: Quat,
+    /// Shape of the part
+    pub shape: ColliderShape,
+}
+
+impl CompoundPart {
+    /// Create a part at an offset
+    #[must_use]
+    pub fn new(shape: ColliderShape, offset: Vec3) -> Self {
+        Self {
+            offset,
+            rotation: Quat::IDENTITY,
+            shape,
+        }
+    }
+
+    /// Set the rotation relative to the collider
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+impl ColliderShape {
+    /// Create the rapier shape
+    ///
+    /// A triangle mesh without triangles or with out-of-range indices, a
+    /// convex hull that can't be computed, e.g. from fewer than four points,
+    /// or a compound without usable parts falls back to a zero-sized ball.
+    /// Nested compounds are flattened and triangle mesh parts are skipped.
+    pub(super) fn shared_shape(&self) -> SharedShape {
+        match self {
+            Self::Cuboid { half_extents } => {
+                SharedShape::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            }
+            Self::Ball { radius } => SharedShape::ball(*radius),
+            Self::Capsule {
+                half_height,
+                radius,
+            } => SharedShape::capsule_y(*half_height, *radius),
+            Self::TriMesh { vertices, indices } => {
+                let in_bounds = indices
+                    .iter()
+                    .flatten()
+                    .all(|&i| (i as usize) < vertices.len());
+                if indices.is_empty() || !in_bounds {
+                    log::warn!("Invalid triangle mesh collider, using an empty shape");
+                    return SharedShape::ball(0.0);
+                }
+                SharedShape::trimesh(
+                    vertices.iter().map(|&v| to_point(v)).collect(),
+                    indices.clone(),
+                )
+            }
+            Self::ConvexHull { points } => {
+                let points: Vec<_> = points.iter().map(|&v| to_point(v)).collect();
+                SharedShape::convex_hull(&points).unwrap_or_else(|| {
+                    log::warn!("Degenerate convex hull collider, using an empty shape");
+                    SharedShape::ball(0.0)
+                })
+            }
+            Self::Compound { parts } => {
+                let mut shapes = Vec::new();
+                flatten_compound(parts, Isometry3::identity(), &mut shapes);
+                if shapes.is_empty() {
+                    log::warn!("Empty compound collider, using an empty shape");
+                    return SharedShape::ball(0.0);
+                }
+                SharedShape::compound(shapes)
+            }
+        }
+    }
+}
+
+/// Collect the parts of a compound, flattening nested compounds
+///
+/// Triangle meshes can't be compound parts, so they are skipped.
+fn flatten_compound(
+    parts: &[CompoundPart],
+    parent: Isometry3<f32>,
+    shapes: &mut Vec<(Isometry3<f32>, SharedShape)>,
+) {
+    for part in parts {
+        let position = parent * isometry(part.offset, part.rotation);
+        match &part.shape {
+            ColliderShape::Compound { parts } => flatten_compound(parts, position, shapes),
+            ColliderShape::TriMesh { .. } => {
+                log::warn!("Triangle mesh in a compound collider, skipping it");
+            }
+            shape => shapes.push((position, shape.shared_shape())),
+        }
+    }
+}
+
+/// Collider attached to the entity's rigid body
+///
+/// The entity's transform scale is not applied to the shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collider {
+    /// Collision shape
+    pub shape: ColliderShape,
+    /// Offset from the body origin
+    pub offset: Vec3,
+    /// Mass density
+    pub density: f32,
+    /// Friction coefficient
+    pub friction: f32,
+    /// Bounciness (0.0 to 1.0)
+    pub restitution: f32,
+    /// How friction is combined with the other collider's
+    #[serde(default)]
+    pub friction_combine: CombineRule,
+    /// How restitution is combined with the other collider's
+    #[serde(default)]
+    pub restitution_combine: CombineRule,
+    /// Named collision layer, see [`CollisionLayers`](super::CollisionLayers)
+    #[serde(default)]
+    pub layer: Option<String>,
+    /// Detect overlaps without generating contact forces
+    pub sensor: bool,
+    /// Report collision start and stop events
+    #[serde(default)]
+    pub collision_events: bool,
+    /// Report contact forces above this magnitude
+    #[serde(default)]
+    pub contact_force_threshold: Option<f32>,
+}
+
+impl Collider {
+    /// Create a collider with default material
+    #[must_use]
+    pub fn new(shape: ColliderShape) -> Self {
+        Self {
+            shape,
+            offset: Vec3::ZERO,
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+            friction_combine: CombineRule::Average,
+            restitution_combine: CombineRule::Average,
+            layer: None,
+            sensor: false,
+            collision_events: false,
+            contact_force_threshold: None,
+        }
+    }
+
+    /// Create a box collider
+    #[must_use]
+    pub fn cuboid(half_extents: Vec3) -> Self {
+        Self::new(ColliderShape::Cuboid { half_extents })
+    }
+
+    /// Create a sphere collider
+    #[must_use]
+    pub fn ball(radius: f32) -> Self {
+        Self::new(ColliderShape::Ball { radius })
+    }
+
+    /// Create a Y-axis capsule collider
+    #[must_use]
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Self::new(ColliderShape::Capsule {
+            half_height,
+            radius,
+        })
+    }
+
+    /// Set the offset from the body origin
+    #[must_use]
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set the density
+    #[must_use]
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Set the friction coefficient
+    #[must_use]
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// Set the restitution
+    #[must_use]
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    /// Make the collider a sensor
+    #[must_use]
+    pub fn sensor(mut self) -> Self {
+        self.sensor = true;
+        self
+    }
+
+    /// Report collision start and stop events for this collider
+    #[must_use]
+    pub fn with_collision_events(mut self) -> Self {
+        self.collision_events = true;
+        self
+    }
+
+    /// Report contact force events when the total force exceeds `threshold`
+    #[must_use]
+    pub fn with_contact_force_events(mut self, threshold: f32) -> Self {
+        self.contact_force_threshold = Some(threshold);
+        self
+    }
+
+    /// Create a rapier collider builder from this description
+    pub(super) fn builder(&self) -> ColliderBuilder {
+        ColliderBuilder::new(self.shape.shared_shape())
+            .translation(Vector3::new(self.offset.x, self.offset.y, self.offset.z))
+            .density(self.density)
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .friction_combine_rule(self.friction_combine.into())
+            .restitution_combine_rule(self.restitution_combine.into())
+            .sensor(self.sensor)
+            .active_collision_types(if self.sensor {
+                sensor_collision_types()
+            } else {
+                ActiveCollisionTypes::default()
+            })
+            .active_events(active_events(
+                self.collision_events,
+                self.contact_force_threshold.is_some(),
+            ))
+            .contact_force_event_threshold(self.contact_force_threshold.unwrap_or(0.0))
+    }
+}
+
+/// Convert a position to a rapier point
+pub(super) fn to_point(v: Vec3) -> Point3<f32> {
+    Point3::new(v.x, v.y, v.z)
+}
+
+/// Build rapier event flags
+pub(super) fn active_events(collisions: bool, contact_forces: bool) -> ActiveEvents {
+    let mut events = ActiveEvents::empty();
+    if collisions {
+        events |= ActiveEvents::COLLISION_EVENTS;
+    }
+    if contact_forces {
+        events |= ActiveEvents::CONTACT_FORCE_EVENTS;
+    }
+    events
+}
+
+/// Register the physics components as cloneable, so templates and pools copy them
+pub fn register_physics_components(registry: &mut TypeRegistry) {
+    registry.register_clone::<RigidBody>();
+    registry.register_clone::<Collider>();
+}
@@ -0,0 +1,437 @@
+//! Collision layers and physics config files
+//!
+//! [`CollisionLayers`] names up to 32 layers and which pairs of them collide.
+//! Together with named [`PhysicsMaterial`]s it makes up a [`PhysicsConfig`]
+//! that can be loaded from RON or JSON:
+//!
+//! ```ron
+//! (
+//!     layers: (
+//!         layers: ["default", "player", "enemy", "debris"],
+//!         ignored: [("player", "debris"), ("debris", "debris")],
+//!     ),
+//!     materials: {
+//!         "ice": (friction: 0.02, restitution: 0.0, friction_combine: Min),
+//!     },
+//! )
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use rapier3d::prelude::{Group, InteractionGroups};
+use serde::{Deserialize, Serialize};
+
+use super::components::Collider;
+use super::materials::PhysicsMaterial;
+use super::query::QueryFilter;
+use super::world::{ColliderHandle, Physics};
+
+/// Maximum number of collision layers
+pub const MAX_COLLISION_LAYERS: usize = 32;
+
+/// Named collision layers and the pairs of layers that don't collide
+///
+/// Every pair of layers collides unless ignored. Colliders without a layer
+/// collide with everything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionLayers {
+    layers: Vec<String>,
+    #[serde(default)]
+    ignored: Vec<(String, String)>,
+}
+
+impl CollisionLayers {
+    /// Create layers with only a `"default"` layer
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            layers: vec!["default".to_string()],
+            ignored: Vec::new(),
+        }
+    }
+
+    /// Add a layer and return its index
+    pub fn add_layer(&mut self, name: impl Into<String>) -> Result<usize, PhysicsConfigError> {
+        let name = name.into();
+        if self.index(&name).is_some() {
+            return Err(PhysicsConfigError::DuplicateLayer(name));
+        }
+        if self.layers.len() >= MAX_COLLISION_LAYERS {
+            return Err(PhysicsConfigError::TooManyLayers);
+        }
+        self.layers.push(name);
+        Ok(self.layers.len() - 1)
+    }
+
+    /// Add a layer, builder style
+    pub fn with_layer(mut self, name: impl Into<String>) -> Result<Self, PhysicsConfigError> {
+        self.add_layer(name)?;
+        Ok(self)
+    }
+
+    /// Get the index of a layer
+    #[must_use]
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|l| l == name)
+    }
+
+    /// Iterate over layer names in index order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(String::as_str)
+    }
+
+    /// Set whether two layers collide
+    pub fn set_collides(
+        &mut self,
+        a: &str,
+        b: &str,
+        collides: bool,
+    ) -> Result<(), PhysicsConfigError> {
+        self.require(a)?;
+        self.require(b)?;
+        self.ignored
+            .retain(|(x, y)| !((x == a && y == b) || (x == b && y == a)));
+        if !collides {
+            self.ignored.push((a.to_string(), b.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Check if two layers collide
+    #[must_use]
+    pub fn collides(&self, a: &str, b: &str) -> bool {
+        !self
+            .ignored
+            .iter()
+            .any(|(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+
+    /// Get the `(memberships, filter)` bit masks of a layer
+    ///
+    /// These are the masks [`QueryFilter::with_groups`] expects.
+    #[must_use]
+    pub fn groups(&self, layer: &str) -> Option<(u32, u32)> {
+        let bit = |i: usize| u32::try_from(i).ok().and_then(|i| 1u32.checked_shl(i));
+        let membership = bit(self.index(layer)?)?;
+        let filter = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| !self.collides(layer, other))
+            .fold(u32::MAX, |mask, (i, _)| mask & !bit(i).unwrap_or(0));
+        Some((membership, filter))
+    }
+
+    /// Get the rapier interaction groups of a layer
+    pub(super) fn interaction_groups(&self, layer: &str) -> Option<InteractionGroups> {
+        let (memberships, filter) = self.groups(layer)?;
+        Some(InteractionGroups::new(
+            Group::from_bits_truncate(memberships),
+            Group::from_bits_truncate(filter),
+        ))
+    }
+
+    /// Check for duplicate or unknown layers, e.g. after loading
+    pub fn validate(&self) -> Result<(), PhysicsConfigError> {
+        if self.layers.len() > MAX_COLLISION_LAYERS {
+            return Err(PhysicsConfigError::TooManyLayers);
+        }
+        for (i, name) in self.layers.iter().enumerate() {
+            if self.layers[..i].contains(name) {
+                return Err(PhysicsConfigError::DuplicateLayer(name.clone()));
+            }
+        }
+        for (a, b) in &self.ignored {
+            self.require(a)?;
+            self.require(b)?;
+        }
+        Ok(())
+    }
+
+    fn require(&self, name: &str) -> Result<usize, PhysicsConfigError> {
+        self.index(name)
+            .ok_or_else(|| PhysicsConfigError::UnknownLayer(name.to_string()))
+    }
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collision layers and named materials, usually loaded from a file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsConfig {
+    /// Collision layers
+    #[serde(default)]
+    pub layers: CollisionLayers,
+    /// Materials by name
+    #[serde(default)]
+    pub materials: BTreeMap<String, PhysicsMaterial>,
+}
+
+impl PhysicsConfig {
+    /// Get a material by name
+    #[must_use]
+    pub fn material(&self, name: &str) -> Option<PhysicsMaterial> {
+        self.materials.get(name).copied()
+    }
+
+    /// Serialize to a RON string
+    pub fn to_ron(&self) -> Result<String, PhysicsConfigError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| PhysicsConfigError::SerializeError(e.to_string()))
+    }
+
+    /// Deserialize from a RON string
+    pub fn from_ron(content: &str) -> Result<Self, PhysicsConfigError> {
+        let config: Self = ron::from_str(content)
+            .map_err(|e| PhysicsConfigError::DeserializeError(e.to_string()))?;
+        config.layers.validate()?;
+        Ok(config)
+    }
+
+    /// Serialize to a JSON string
+    pub fn to_json(&self) -> Result<String, PhysicsConfigError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PhysicsConfigError::SerializeError(e.to_string()))
+    }
+
+    /// Deserialize from a JSON string
+    pub fn from_json(content: &str) -> Result<Self, PhysicsConfigError> {
+        let config: Self = serde_json::from_str(content)
+            .map_err(|e| PhysicsConfigError::DeserializeError(e.to_string()))?;
+        config.layers.validate()?;
+        Ok(config)
+    }
+
+    /// Load from a RON file
+    pub fn load_ron(path: impl AsRef<Path>) -> Result<Self, PhysicsConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| PhysicsConfigError::IoError(e.to_string()))?;
+        Self::from_ron(&content)
+    }
+
+    /// Load from a JSON file
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self, PhysicsConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| PhysicsConfigError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+}
+
+/// Errors that can occur with collision layers and physics config files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhysicsConfigError {
+    /// IO error
+    IoError(String),
+    /// Serialization error
+    SerializeError(String),
+    /// Deserialization error
+    DeserializeError(String),
+    /// A layer name is not defined
+    UnknownLayer(String),
+    /// A layer name is defined twice
+    DuplicateLayer(String),
+    /// More than [`MAX_COLLISION_LAYERS`] layers
+    TooManyLayers,
+}
+
+impl std::fmt::Display for PhysicsConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "IO error: {e}"),
+            Self::SerializeError(e) => write!(f, "Serialization error: {e}"),
+            Self::DeserializeError(e) => write!(f, "Deserialization error: {e}"),
+            Self::UnknownLayer(name) => write!(f, "Unknown collision layer: {name}"),
+            Self::DuplicateLayer(name) => write!(f, "Duplicate collision layer: {name}"),
+            Self::TooManyLayers => {
+                write!(f, "More than {MAX_COLLISION_LAYERS} collision layers")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PhysicsConfigError {}
+
+impl Collider {
+    /// Put the collider on a named collision layer
+    #[must_use]
+    pub fn with_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
+}
+
+impl QueryFilter<'_> {
+    /// Only consider colliders that collide with a layer
+    ///
+    /// Unknown layers leave the filter unchanged.
+    #[must_use]
+    pub fn with_layer(self, layers: &CollisionLayers, layer: &str) -> Self {
+        match layers.groups(layer) {
+            Some((memberships, filter)) => self.with_groups(memberships, filter),
+            None => self,
+        }
+    }
+}
+
+impl Physics {
+    /// Get the collision layers
+    #[must_use]
+    pub fn collision_layers(&self) -> &CollisionLayers {
+        &self.collision_layers
+    }
+
+    /// Replace the collision layers
+    ///
+    /// Only affects colliders created afterwards; use
+    /// [`Physics::set_collider_layer`] to move existing ones.
+    pub fn set_collision_layers(
+        &mut self,
+        layers: CollisionLayers,
+    ) -> Result<(), PhysicsConfigError> {
+        layers.validate()?;
+        self.collision_layers = layers;
+        Ok(())
+    }
+
+    /// Move a collider to a named layer
+    pub fn set_collider_layer(
+        &mut self,
+        collider: ColliderHandle,
+        layer: &str,
+    ) -> Result<(), PhysicsConfigError> {
+        let groups = self
+            .collision_layers
+            .interaction_groups(layer)
+            .ok_or_else(|| PhysicsConfigError::UnknownLayer(layer.to_string()))?;
+        if let Some(c) = self.collider_set.get_mut(collider.0) {
+            c.set_collision_groups(groups);
+            c.set_solver_groups(groups);
+        }
+        Ok(())
+    }
+
+    /// Get the interaction groups for a collider's optional layer
+    pub(super) fn layer_groups(&self, layer: Option<&str>) -> InteractionGroups {
+        let Some(layer) = layer else {
+            return InteractionGroups::all();
+        };
+        self.collision_layers
+            .interaction_groups(layer)
+            .unwrap_or_else(|| {
+                log::warn!("Unknown collision layer '{layer}', colliding with everything");
+                InteractionGroups::all()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Transform, World};
+    use crate::physics::{CombineRule, RigidBody};
+    use glam::Vec3;
+
+    const CONFIG: &str = r#"(
+        layers: (
+            layers: ["default", "player", "debris"],
+            ignored: [("player", "debris")],
+        ),
+        materials: {
+            "ice": (friction: 0.02, restitution: 0.0, friction_combine: Min),
+        },
+    )"#;
+
+    #[test]
+    fn test_load_config() {
+        let config = PhysicsConfig::from_ron(CONFIG).unwrap();
+        assert!(!config.layers.collides("debris", "player"));
+        assert!(config.layers.collides("debris", "debris"));
+        assert_eq!(config.layers.groups("player"), Some((0b010, !0b100)));
+        assert_eq!(
+            config.material("ice").unwrap().friction_combine,
+            CombineRule::Min
+        );
+        assert_eq!(
+            PhysicsConfig::from_ron(&config.to_ron().unwrap()).unwrap(),
+            config
+        );
+        assert_eq!(
+            PhysicsConfig::from_json(&config.to_json().unwrap()).unwrap(),
+            config
+        );
+
+        let bad = CONFIG.replace(r#"("player", "debris")"#, r#"("player", "ghost")"#);
+        assert_eq!(
+            PhysicsConfig::from_ron(&bad),
+            Err(PhysicsConfigError::UnknownLayer("ghost".to_string()))
+        );
+        let mut layers = CollisionLayers::new();
+        for i in 1..MAX_COLLISION_LAYERS {
+            layers.add_layer(format!("layer{i}")).unwrap();
+        }
+        assert_eq!(
+            layers.add_layer("one more"),
+            Err(PhysicsConfigError::TooManyLayers)
+        );
+
+        // Deserializing skips the limit, so it is checked when applied
+        let names: Vec<String> = (0..40).map(|i| format!("\"l{i}\"")).collect();
+        let json = format!("{{\"layers\": [{}]}}", names.join(", "));
+        let too_many: CollisionLayers = serde_json::from_str(&json).unwrap();
+        assert_eq!(too_many.groups("l35"), None);
+        assert_eq!(too_many.groups("l0").map(|(m, _)| m), Some(1));
+        assert_eq!(
+            Physics::new().set_collision_layers(too_many),
+            Err(PhysicsConfigError::TooManyLayers)
+        );
+    }
+
+    #[test]
+    fn test_layers_filter_collisions_and_queries() {
+        let config = PhysicsConfig::from_ron(CONFIG).unwrap();
+        let mut physics = Physics::new();
+        physics.set_collision_layers(config.layers.clone()).unwrap();
+        let mut world = World::new();
+        world.spawn((
+            Transform::default(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::new(10.0, 0.5, 10.0)),
+        ));
+        let drop = |world: &mut World, x: f32, layer: &str| {
+            world.spawn((
+                Transform::from_position(Vec3::new(x, 1.0, 0.0)),
+                RigidBody::dynamic(),
+                Collider::ball(0.5).with_layer(layer),
+            ))
+        };
+        let player = drop(&mut world, 0.0, "player");
+        let debris = drop(&mut world, 0.0, "debris");
+        let debris_on_debris = drop(&mut world, 0.0, "debris");
+        world.get_mut::<Transform>(debris).unwrap().position.y = 3.0;
+        world
+            .get_mut::<Transform>(debris_on_debris)
+            .unwrap()
+            .position = Vec3::new(0.0, 5.0, 0.0);
+        for _ in 0..120 {
+            physics.step_world(&mut world, 1.0 / 60.0);
+        }
+
+        let y = |e| world.get::<Transform>(e).unwrap().position.y;
+        assert!((y(player) - 1.0).abs() < 0.05);
+        // Debris falls through the player but lands on the floor, and debris stacks
+        assert!((y(debris) - 1.0).abs() < 0.05, "y = {}", y(debris));
+        assert!((y(debris_on_debris) - 2.0).abs() < 0.05);
+
+        let filter = QueryFilter::new().with_layer(physics.collision_layers(), "player");
+        let hit = physics
+            .raycast_filtered(Vec3::new(0.0, 10.0, 0.0), Vec3::NEG_Y, 20.0, &filter)
+            .unwrap();
+        assert_eq!(hit.entity, Some(player));
+    }
+}
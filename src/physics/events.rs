@@ -0,0 +1,291 @@
+//! Collision and contact force events
+//!
+//! Events are collected while [`Physics::step`](super::Physics::step) runs and
+//! kept until drained. Colliders only report events they opted into, see
+//! [`Collider::with_collision_events`](super::Collider::with_collision_events)
+//! and [`Collider::with_contact_force_events`](super::Collider::with_contact_force_events).
+
+use std::sync::{Mutex, MutexGuard};
+
+use glam::Vec3;
+use hecs::Entity;
+use rapier3d::prelude::{
+    ColliderSet, CollisionEvent as RapierCollisionEvent, ContactPair, EventHandler, Real,
+    RigidBodySet,
+};
+
+use super::sync::user_data_to_entity;
+use super::world::ColliderHandle;
+
+/// A contact point between two colliders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    /// World-space position on the first collider
+    pub point: Vec3,
+    /// Penetration depth (negative while the shapes are still apart)
+    pub depth: f32,
+    /// Impulse applied at this point during the step
+    pub impulse: f32,
+}
+
+/// Whether a collision began or ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollisionEventKind {
+    /// Colliders started touching
+    Started,
+    /// Colliders stopped touching, or one of them was removed
+    Stopped,
+}
+
+/// Two colliders started or stopped touching
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionEvent {
+    /// Started or stopped
+    pub kind: CollisionEventKind,
+    /// First collider
+    pub collider1: ColliderHandle,
+    /// Second collider
+    pub collider2: ColliderHandle,
+    /// Entity of the first collider, for colliders created from components
+    pub entity1: Option<Entity>,
+    /// Entity of the second collider, for colliders created from components
+    pub entity2: Option<Entity>,
+    /// Whether one of the colliders is a sensor
+    pub sensor: bool,
+    /// World-space contact normal pointing from the first to the second collider
+    pub normal: Vec3,
+    /// Contact points, empty for sensors and stopped collisions
+    pub contacts: Vec<ContactPoint>,
+}
+
+impl CollisionEvent {
+    /// Check if the colliders started touching
+    #[must_use]
+    pub fn started(&self) -> bool {
+        self.kind == CollisionEventKind::Started
+    }
+
+    /// Check if the colliders stopped touching
+    #[must_use]
+    pub fn stopped(&self) -> bool {
+        self.kind == CollisionEventKind::Stopped
+    }
+
+    /// Check if an entity is one of the pair
+    #[must_use]
+    pub fn involves(&self, entity: Entity) -> bool {
+        self.entity1 == Some(entity) || self.entity2 == Some(entity)
+    }
+
+    /// Get the other entity of the pair
+    #[must_use]
+    pub fn other(&self, entity: Entity) -> Option<Entity> {
+        if self.entity1 == Some(entity) {
+            self.entity2
+        } else if self.entity2 == Some(entity) {
+            self.entity1
+        } else {
+            None
+        }
+    }
+}
+
+/// Contact force between two colliders exceeded their threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactForceEvent {
+    /// First collider
+    pub collider1: ColliderHandle,
+    /// Second collider
+    pub collider2: ColliderHandle,
+    /// Entity of the first collider, for colliders created from components
+    pub entity1: Option<Entity>,
+    /// Entity of the second collider, for colliders created from components
+    pub entity2: Option<Entity>,
+    /// Sum of the contact forces applied during the step
+    pub total_force: Vec3,
+    /// Magnitude of the summed forces
+    pub total_force_magnitude: f32,
+    /// World-space contact normal pointing from the first to the second collider
+    pub normal: Vec3,
+    /// Contact points with their impulses
+    pub contacts: Vec<ContactPoint>,
+}
+
+/// Event handler that stores events until drained
+#[derive(Debug, Default)]
+pub(super) struct EventCollector {
+    collisions: Mutex<Vec<CollisionEvent>>,
+    contact_forces: Mutex<Vec<ContactForceEvent>>,
+}
+
+impl EventCollector {
+    /// Take all collected collision events
+    pub(super) fn drain_collisions(&self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut *lock(&self.collisions))
+    }
+
+    /// Take all collected contact force events
+    pub(super) fn drain_contact_forces(&self) -> Vec<ContactForceEvent> {
+        std::mem::take(&mut *lock(&self.contact_forces))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn to_vec3(v: &rapier3d::prelude::Vector<Real>) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+/// Get the entity of a collider from its user data
+fn collider_entity(
+    colliders: &ColliderSet,
+    handle: rapier3d::prelude::ColliderHandle,
+) -> Option<Entity> {
+    colliders
+        .get(handle)
+        .and_then(|c| user_data_to_entity(c.user_data))
+}
+
+/// Extract the normal and world-space contact points of a pair
+fn contact_data(colliders: &ColliderSet, pair: &ContactPair) -> (Vec3, Vec<ContactPoint>) {
+    let Some(collider1) = colliders.get(pair.collider1) else {
+        return (Vec3::ZERO, Vec::new());
+    };
+
+    let mut normal = Vec3::ZERO;
+    let mut contacts = Vec::new();
+    for manifold in pair.manifolds.iter().filter(|m| !m.points.is_empty()) {
+        if normal == Vec3::ZERO {
+            normal = to_vec3(&manifold.data.normal);
+        }
+        let position = match manifold.subshape_pos1 {
+            Some(subshape) => collider1.position() * subshape,
+            None => *collider1.position(),
+        };
+        contacts.extend(manifold.points.iter().map(|p| {
+            let point = position * p.local_p1;
+            ContactPoint {
+                point: Vec3::new(point.x, point.y, point.z),
+                depth: -p.dist,
+                impulse: p.data.impulse,
+            }
+        }));
+    }
+    (normal, contacts)
+}
+
+impl EventHandler for EventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: RapierCollisionEvent,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        let (handle1, handle2) = (event.collider1(), event.collider2());
+        let kind = if event.started() {
+            CollisionEventKind::Started
+        } else {
+            CollisionEventKind::Stopped
+        };
+        let (normal, contacts) = match contact_pair {
+            Some(pair) if kind == CollisionEventKind::Started => contact_data(colliders, pair),
+            _ => (Vec3::ZERO, Vec::new()),
+        };
+
+        lock(&self.collisions).push(CollisionEvent {
+            kind,
+            collider1: ColliderHandle(handle1),
+            collider2: ColliderHandle(handle2),
+            entity1: collider_entity(colliders, handle1),
+            entity2: collider_entity(colliders, handle2),
+            sensor: event.sensor(),
+            normal,
+            contacts,
+        });
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        dt: Real,
+        _bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        contact_pair: &ContactPair,
+        total_force_magnitude: Real,
+    ) {
+        let (normal, contacts) = contact_data(colliders, contact_pair);
+        let total_force = if dt > 0.0 {
+            to_vec3(&contact_pair.total_impulse()) / dt
+        } else {
+            Vec3::ZERO
+        };
+
+        lock(&self.contact_forces).push(ContactForceEvent {
+            collider1: ColliderHandle(contact_pair.collider1),
+            collider2: ColliderHandle(contact_pair.collider2),
+            entity1: collider_entity(colliders, contact_pair.collider1),
+            entity2: collider_entity(colliders, contact_pair.collider2),
+            total_force,
+            total_force_magnitude,
+            normal,
+            contacts,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ecs::{Transform, World};
+    use crate::physics::{Collider, Physics, RigidBody};
+    use glam::Vec3;
+
+    fn drop_ball(ball: Collider) -> (Physics, World, hecs::Entity, hecs::Entity) {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        let ground = world.spawn((
+            Transform::default(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::new(10.0, 0.5, 10.0)),
+        ));
+        let ball = world.spawn((
+            Transform::from_position(Vec3::new(0.0, 1.6, 0.0)),
+            RigidBody::dynamic(),
+            ball,
+        ));
+        for _ in 0..60 {
+            physics.step_world(&mut world, 1.0 / 60.0);
+        }
+        (physics, world, ground, ball)
+    }
+
+    #[test]
+    fn test_collision_started_with_contacts() {
+        let (mut physics, _, ground, ball) = drop_ball(Collider::ball(0.5).with_collision_events());
+
+        let events = physics.drain_collision_events();
+        let event = events.iter().find(|e| e.started()).expect("landing event");
+        assert!(event.involves(ball));
+        assert_eq!(event.other(ball), Some(ground));
+        assert!(!event.sensor);
+        assert!(!event.contacts.is_empty());
+        assert!(event.normal.y.abs() > 0.9);
+        assert!(physics.drain_collision_events().is_empty());
+    }
+
+    #[test]
+    fn test_events_are_opt_in_with_force_threshold() {
+        let (mut physics, ..) = drop_ball(Collider::ball(0.5));
+        assert!(physics.drain_collision_events().is_empty());
+        assert!(physics.drain_contact_force_events().is_empty());
+
+        let (mut physics, ..) = drop_ball(Collider::ball(0.5).with_contact_force_events(1.0));
+        let forces = physics.drain_contact_force_events();
+        assert!(!forces.is_empty());
+        assert!(forces.iter().all(|e| e.total_force_magnitude > 1.0));
+
+        let (mut physics, ..) = drop_ball(Collider::ball(0.5).with_contact_force_events(1e9));
+        assert!(physics.drain_contact_force_events().is_empty());
+    }
+}
@@ -0,0 +1,512 @@
+//! Joints between rigid bodies
+//!
+//! A [`Joint`] describes a constraint between two bodies; create it with
+//! [`Physics::create_joint`] and refer to it afterwards by [`JointHandle`].
+
+use glam::Vec3;
+use rapier3d::na::{Point3, Unit, Vector3};
+use rapier3d::prelude::{
+    FixedJointBuilder, GenericJoint, ImpulseJointHandle, JointAxis, PrismaticJointBuilder,
+    RevoluteJointBuilder, RopeJointBuilder, SphericalJointBuilder,
+};
+
+use super::world::{Physics, RigidBodyHandle};
+
+/// Handle to a joint in the physics world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JointHandle(pub ImpulseJointHandle);
+
+/// Kind of joint and its free axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointType {
+    /// No relative motion
+    Fixed,
+    /// Rotation around an axis, like a hinge
+    Revolute { axis: Vec3 },
+    /// Translation along an axis, like a slider
+    Prismatic { axis: Vec3 },
+    /// Free rotation around the anchor, like a ball-and-socket
+    Spherical,
+    /// Distance between the anchors can't exceed `max_distance`
+    Rope { max_distance: f32 },
+}
+
+impl JointType {
+    /// Axes that limits and motors apply to
+    fn free_axes(&self) -> &'static [JointAxis] {
+        match self {
+            Self::Fixed => &[],
+            Self::Revolute { .. } => &[JointAxis::AngX],
+            Self::Prismatic { .. } | Self::Rope { .. } => &[JointAxis::LinX],
+            Self::Spherical => &[JointAxis::AngX, JointAxis::AngY, JointAxis::AngZ],
+        }
+    }
+}
+
+/// Motor driving a joint's free axis towards a target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointMotor {
+    /// Target velocity (radians or units per second)
+    pub target_velocity: f32,
+    /// Target position (radians or units)
+    pub target_position: f32,
+    /// Strength of the pull towards the target position
+    pub stiffness: f32,
+    /// Strength of the pull towards the target velocity
+    pub damping: f32,
+    /// Maximum force or torque the motor can apply
+    pub max_force: Option<f32>,
+}
+
+impl JointMotor {
+    /// Create a motor that drives the joint at a target velocity
+    #[must_use]
+    pub const fn velocity(target_velocity: f32, damping: f32) -> Self {
+        Self {
+            target_velocity,
+            target_position: 0.0,
+            stiffness: 0.0,
+            damping,
+            max_force: None,
+        }
+    }
+
+    /// Create a motor that drives the joint to a target position like a spring
+    #[must_use]
+    pub const fn position(target_position: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            target_velocity: 0.0,
+            target_position,
+            stiffness,
+            damping,
+            max_force: None,
+        }
+    }
+
+    /// Set the maximum force or torque
+    #[must_use]
+    pub const fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = Some(max_force);
+        self
+    }
+}
+
+/// Joint description
+///
+/// ```ignore
+/// let hinge = Joint::revolute(Vec3::Y)
+///     .with_anchors(Vec3::new(0.5, 0.0, 0.0), Vec3::new(-0.5, 0.0, 0.0))
+///     .with_limits(-1.0, 1.0)
+///     .with_breaking_force(500.0);
+/// let handle = physics.create_joint(door_frame, door, &hinge);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Joint {
+    /// Kind of joint
+    pub joint_type: JointType,
+    /// Anchor in the first body's local space
+    pub anchor1: Vec3,
+    /// Anchor in the second body's local space
+    pub anchor2: Vec3,
+    /// Minimum and maximum of the free axis; applied to every axis of spherical joints
+    pub limits: Option<(f32, f32)>,
+    /// Motor on the free axis; applied to every axis of spherical joints
+    pub motor: Option<JointMotor>,
+    /// Linear force above which the joint breaks
+    pub breaking_force: Option<f32>,
+    /// Whether the two bodies collide with each other
+    pub contacts_enabled: bool,
+}
+
+impl Joint {
+    /// Create a joint of the given type anchored at both body origins
+    #[must_use]
+    pub const fn new(joint_type: JointType) -> Self {
+        Self {
+            joint_type,
+            anchor1: Vec3::ZERO,
+            anchor2: Vec3::ZERO,
+            limits: None,
+            motor: None,
+            breaking_force: None,
+            contacts_enabled: true,
+        }
+    }
+
+    /// Create a fixed joint
+    #[must_use]
+    pub const fn fixed() -> Self {
+        Self::new(JointType::Fixed)
+    }
+
+    /// Create a revolute joint rotating around `axis`
+    #[must_use]
+    pub const fn revolute(axis: Vec3) -> Self {
+        Self::new(JointType::Revolute { axis })
+    }
+
+    /// Create a prismatic joint sliding along `axis`
+    #[must_use]
+    pub const fn prismatic(axis: Vec3) -> Self {
+        Self::new(JointType::Prismatic { axis })
+    }
+
+    /// Create a spherical joint
+    #[must_use]
+    pub const fn spherical() -> Self {
+        Self::new(JointType::Spherical)
+    }
+
+    /// Create a rope joint with a maximum length
+    #[must_use]
+    pub const fn rope(max_distance: f32) -> Self {
+        Self::new(JointType::Rope { max_distance })
+    }
+
+    /// Set the anchors in each body's local space
+    #[must_use]
+    pub const fn with_anchors(mut self, anchor1: Vec3, anchor2: Vec3) -> Self {
+        self.anchor1 = anchor1;
+        self.anchor2 = anchor2;
+        self
+    }
+
+    /// Limit the free axis to `min..=max`
+    #[must_use]
+    pub const fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some((min, max));
+        self
+    }
+
+    /// Drive the free axis with a motor
+    #[must_use]
+    pub const fn with_motor(mut self, motor: JointMotor) -> Self {
+        self.motor = Some(motor);
+        self
+    }
+
+    /// Break the joint when its linear force exceeds `force`
+    #[must_use]
+    pub const fn with_breaking_force(mut self, force: f32) -> Self {
+        self.breaking_force = Some(force);
+        self
+    }
+
+    /// Set whether the two bodies collide with each other
+    #[must_use]
+    pub const fn with_contacts(mut self, enabled: bool) -> Self {
+        self.contacts_enabled = enabled;
+        self
+    }
+
+    /// Build the rapier joint
+    fn build(&self) -> GenericJoint {
+        let anchor1 = Point3::new(self.anchor1.x, self.anchor1.y, self.anchor1.z);
+        let anchor2 = Point3::new(self.anchor2.x, self.anchor2.y, self.anchor2.z);
+        let mut joint: GenericJoint = match self.joint_type {
+            JointType::Fixed => FixedJointBuilder::new().into(),
+            JointType::Revolute { axis } => RevoluteJointBuilder::new(unit(axis)).into(),
+            JointType::Prismatic { axis } => PrismaticJointBuilder::new(unit(axis)).into(),
+            JointType::Spherical => SphericalJointBuilder::new().into(),
+            JointType::Rope { max_distance } => RopeJointBuilder::new(max_distance).into(),
+        };
+        joint
+            .set_local_anchor1(anchor1)
+            .set_local_anchor2(anchor2)
+            .set_contacts_enabled(self.contacts_enabled);
+        if let Some((min, max)) = self.limits {
+            set_limits(&mut joint, self.joint_type, min, max);
+        }
+        if let Some(motor) = self.motor {
+            set_motor(&mut joint, self.joint_type, motor);
+        }
+        joint
+    }
+}
+
+/// A joint that broke this step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JointBroken {
+    /// The removed joint
+    pub joint: JointHandle,
+    /// First attached body
+    pub body1: RigidBodyHandle,
+    /// Second attached body
+    pub body2: RigidBodyHandle,
+}
+
+/// Current state of a joint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointState {
+    /// First attached body
+    pub body1: RigidBodyHandle,
+    /// Second attached body
+    pub body2: RigidBodyHandle,
+    /// Position along the free axis: angle for revolute joints, offset for
+    /// prismatic joints, distance for rope joints, total angle for spherical joints
+    pub position: f32,
+    /// Linear force applied by the joint during the last step
+    pub force: Vec3,
+    /// Torque applied by the joint during the last step
+    pub torque: Vec3,
+}
+
+fn unit(axis: Vec3) -> Unit<Vector3<f32>> {
+    let axis = axis.try_normalize().unwrap_or(Vec3::X);
+    Unit::new_unchecked(Vector3::new(axis.x, axis.y, axis.z))
+}
+
+fn set_limits(joint: &mut GenericJoint, joint_type: JointType, min: f32, max: f32) {
+    for &axis in joint_type.free_axes() {
+        joint.set_limits(axis, [min, max]);
+    }
+}
+
+fn set_motor(joint: &mut GenericJoint, joint_type: JointType, motor: JointMotor) {
+    for &axis in joint_type.free_axes() {
+        joint.set_motor(
+            axis,
+            motor.target_position,
+            motor.target_velocity,
+            motor.stiffness,
+            motor.damping,
+        );
+        joint.set_motor_max_force(axis, motor.max_force.unwrap_or(f32::MAX));
+    }
+}
+
+impl Physics {
+    /// Create a joint between two bodies
+    pub fn create_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: &Joint,
+    ) -> JointHandle {
+        let handle = self
+            .impulse_joint_set
+            .insert(body1.0, body2.0, joint.build(), true);
+        self.joint_types.insert(handle, joint.joint_type);
+        if let Some(force) = joint.breaking_force {
+            self.breaking_forces.insert(handle, force);
+        }
+        JointHandle(handle)
+    }
+
+    /// Remove a joint, returns false if it didn't exist
+    pub fn remove_joint(&mut self, joint: JointHandle) -> bool {
+        self.joint_types.remove(&joint.0);
+        self.breaking_forces.remove(&joint.0);
+        self.impulse_joint_set.remove(joint.0, true).is_some()
+    }
+
+    /// Check if a joint exists
+    pub fn has_joint(&self, joint: JointHandle) -> bool {
+        self.impulse_joint_set.get(joint.0).is_some()
+    }
+
+    /// Get the number of joints
+    pub fn joint_count(&self) -> usize {
+        self.impulse_joint_set.len()
+    }
+
+    /// Change or remove the limits of a joint's free axis
+    pub fn set_joint_limits(&mut self, joint: JointHandle, limits: Option<(f32, f32)>) {
+        let Some(&joint_type) = self.joint_types.get(&joint.0) else {
+            return;
+        };
+        let Some(j) = self.impulse_joint_set.get_mut(joint.0) else {
+            return;
+        };
+        match limits {
+            Some((min, max)) => set_limits(&mut j.data, joint_type, min, max),
+            None => {
+                let axes = joint_type.free_axes();
+                for &axis in axes {
+                    j.data.limit_axes.remove(axis.into());
+                }
+            }
+        }
+    }
+
+    /// Change or remove the motor of a joint's free axis
+    pub fn set_joint_motor(&mut self, joint: JointHandle, motor: Option<JointMotor>) {
+        let Some(&joint_type) = self.joint_types.get(&joint.0) else {
+            return;
+        };
+        let Some(j) = self.impulse_joint_set.get_mut(joint.0) else {
+            return;
+        };
+        match motor {
+            Some(motor) => set_motor(&mut j.data, joint_type, motor),
+            None => {
+                for &axis in joint_type.free_axes() {
+                    j.data.motor_axes.remove(axis.into());
+                }
+            }
+        }
+    }
+
+    /// Change or remove the breaking force of a joint
+    pub fn set_joint_breaking_force(&mut self, joint: JointHandle, force: Option<f32>) {
+        if !self.has_joint(joint) {
+            return;
+        }
+        match force {
+            Some(force) => self.breaking_forces.insert(joint.0, force),
+            None => self.breaking_forces.remove(&joint.0),
+        };
+    }
+
+    /// Get the current state of a joint
+    pub fn joint_state(&self, joint: JointHandle) -> Option<JointState> {
+        let j = self.impulse_joint_set.get(joint.0)?;
+        let rb1 = self.rigid_body_set.get(j.body1)?;
+        let rb2 = self.rigid_body_set.get(j.body2)?;
+
+        let frame1 = rb1.position() * j.data.local_frame1;
+        let frame2 = rb2.position() * j.data.local_frame2;
+        let relative = frame1.inv_mul(&frame2);
+        let position = match self.joint_types.get(&joint.0) {
+            Some(JointType::Revolute { .. }) => relative.rotation.scaled_axis().x,
+            Some(JointType::Prismatic { .. }) => relative.translation.vector.x,
+            Some(JointType::Rope { .. }) => relative.translation.vector.norm(),
+            Some(JointType::Spherical) => relative.rotation.angle(),
+            Some(JointType::Fixed) | None => 0.0,
+        };
+
+        let dt = self.integration_parameters.dt;
+        let inv_dt = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+        let impulses = j.impulses;
+        Some(JointState {
+            body1: RigidBodyHandle(j.body1),
+            body2: RigidBodyHandle(j.body2),
+            position,
+            force: Vec3::new(impulses[0], impulses[1], impulses[2]) * inv_dt,
+            torque: Vec3::new(impulses[3], impulses[4], impulses[5]) * inv_dt,
+        })
+    }
+
+    /// Take the joints that broke since the last call
+    pub fn drain_broken_joints(&mut self) -> Vec<JointBroken> {
+        std::mem::take(&mut self.broken_joints)
+    }
+
+    /// Remove joints whose force exceeded their breaking force this step
+    pub(super) fn break_joints(&mut self) {
+        if self.breaking_forces.is_empty() {
+            return;
+        }
+        let dt = self.integration_parameters.dt;
+        if dt <= 0.0 {
+            return;
+        }
+
+        let mut broken = Vec::new();
+        self.breaking_forces.retain(|&handle, &mut max_force| {
+            let Some(joint) = self.impulse_joint_set.get(handle) else {
+                return false;
+            };
+            let force = joint.impulses.fixed_rows::<3>(0).norm() / dt;
+            if force > max_force {
+                broken.push(JointBroken {
+                    joint: JointHandle(handle),
+                    body1: RigidBodyHandle(joint.body1),
+                    body2: RigidBodyHandle(joint.body2),
+                });
+                return false;
+            }
+            true
+        });
+
+        for event in &broken {
+            self.impulse_joint_set.remove(event.joint.0, true);
+            self.joint_types.remove(&event.joint.0);
+        }
+        self.broken_joints.extend(broken);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Quat;
+
+    fn pendulum(joint: &Joint) -> (Physics, RigidBodyHandle, JointHandle) {
+        let mut physics = Physics::new();
+        let anchor = physics.create_static_body(Vec3::ZERO, Quat::IDENTITY);
+        let bob = physics.create_dynamic_body(Vec3::new(2.0, 0.0, 0.0), Quat::IDENTITY);
+        physics.add_sphere_collider(bob, 0.25, 10.0);
+        let handle = physics.create_joint(anchor, bob, joint);
+        (physics, bob, handle)
+    }
+
+    #[test]
+    fn test_rope_limits_distance() {
+        let (mut physics, bob, joint) = pendulum(&Joint::rope(2.0));
+        for _ in 0..120 {
+            physics.step(1.0 / 60.0);
+        }
+
+        let distance = physics.get_position(bob).unwrap().length();
+        assert!(distance <= 2.05, "rope stretched to {distance}");
+        let state = physics.joint_state(joint).unwrap();
+        assert!((state.position - distance).abs() < 0.05);
+        assert!(physics.remove_joint(joint));
+        assert!(!physics.has_joint(joint));
+
+        // Removing a body forgets its joints
+        let (mut physics, bob, _) = pendulum(&Joint::fixed().with_breaking_force(10.0));
+        physics.remove_body(bob);
+        assert!(physics.joint_types.is_empty());
+        assert!(physics.breaking_forces.is_empty());
+        assert_eq!(physics.joint_count(), 0);
+    }
+
+    #[test]
+    fn test_revolute_motor_and_limits() {
+        let joint = Joint::revolute(Vec3::Z)
+            .with_anchors(Vec3::ZERO, Vec3::new(-2.0, 0.0, 0.0))
+            .with_limits(-0.5, 0.5);
+        let (mut physics, _, handle) = pendulum(&joint);
+        for _ in 0..120 {
+            physics.step(1.0 / 60.0);
+        }
+        let angle = physics.joint_state(handle).unwrap().position;
+        assert!((-0.55..=0.55).contains(&angle), "angle {angle}");
+
+        assert!(angle < 0.0, "gravity pulls the arm down");
+
+        // Without gravity the motor alone sets the angle
+        physics.set_joint_limits(handle, None);
+        physics.set_joint_motor(handle, Some(JointMotor::position(1.0, 1e4, 1e3)));
+        physics.gravity = Vec3::ZERO;
+        for _ in 0..240 {
+            physics.step(1.0 / 60.0);
+        }
+        let angle = physics.joint_state(handle).unwrap().position;
+        assert!((angle - 1.0).abs() < 0.05, "angle {angle}");
+    }
+
+    #[test]
+    fn test_joint_breaks_under_load() {
+        let joint = Joint::fixed()
+            .with_anchors(Vec3::ZERO, Vec3::new(-2.0, 0.0, 0.0))
+            .with_breaking_force(1e6);
+        let (mut physics, bob, handle) = pendulum(&joint);
+        physics.step(1.0 / 60.0);
+        assert!(physics.drain_broken_joints().is_empty());
+        assert!(physics.joint_state(handle).unwrap().force.y < 0.0);
+
+        // Gravity on the bob pulls harder than the joint can take
+        physics.set_joint_breaking_force(handle, Some(0.1));
+        physics.step(1.0 / 60.0);
+
+        let broken = physics.drain_broken_joints();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].joint, handle);
+        assert_eq!(broken[0].body2, bob);
+        assert!(!physics.has_joint(handle));
+        assert_eq!(physics.joint_state(handle), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Surface materials
+//!
+//! A [`PhysicsMaterial`] bundles friction and restitution with the rules
+//! used to combine them when two colliders touch.
+
+use rapier3d::prelude::CoefficientCombineRule;
+use serde::{Deserialize, Serialize};
+
+use super::components::Collider;
+use super::world::{ColliderHandle, Physics};
+
+/// How the coefficients of two touching colliders are combined
+///
+/// When the two colliders use different rules, the one listed last here wins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CombineRule {
+    /// Average of both coefficients
+    #[default]
+    Average,
+    /// Smaller coefficient
+    Min,
+    /// Product of both coefficients
+    Multiply,
+    /// Larger coefficient
+    Max,
+}
+
+impl From<CombineRule> for CoefficientCombineRule {
+    fn from(rule: CombineRule) -> Self {
+        match rule {
+            CombineRule::Average => Self::Average,
+            CombineRule::Min => Self::Min,
+            CombineRule::Multiply => Self::Multiply,
+            CombineRule::Max => Self::Max,
+        }
+    }
+}
+
+/// Friction and bounciness of a surface
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsMaterial {
+    /// Friction coefficient
+    pub friction: f32,
+    /// Bounciness (0.0 to 1.0)
+    pub restitution: f32,
+    /// How friction is combined with the other collider's
+    #[serde(default)]
+    pub friction_combine: CombineRule,
+    /// How restitution is combined with the other collider's
+    #[serde(default)]
+    pub restitution_combine: CombineRule,
+}
+
+impl PhysicsMaterial {
+    /// Create a material with average combine rules
+    #[must_use]
+    pub const fn new(friction: f32, restitution: f32) -> Self {
+        Self {
+            friction,
+            restitution,
+            friction_combine: CombineRule::Average,
+            restitution_combine: CombineRule::Average,
+        }
+    }
+
+    /// Set the friction combine rule
+    #[must_use]
+    pub const fn with_friction_combine(mut self, rule: CombineRule) -> Self {
+        self.friction_combine = rule;
+        self
+    }
+
+    /// Set the restitution combine rule
+    #[must_use]
+    pub const fn with_restitution_combine(mut self, rule: CombineRule) -> Self {
+        self.restitution_combine = rule;
+        self
+    }
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self::new(0.5, 0.0)
+    }
+}
+
+impl Collider {
+    /// Set friction, restitution and their combine rules
+    #[must_use]
+    pub fn with_material(mut self, material: PhysicsMaterial) -> Self {
+        self.friction = material.friction;
+        self.restitution = material.restitution;
+        self.friction_combine = material.friction_combine;
+        self.restitution_combine = material.restitution_combine;
+        self
+    }
+
+    /// Get the surface material
+    #[must_use]
+    pub fn material(&self) -> PhysicsMaterial {
+        PhysicsMaterial {
+            friction: self.friction,
+            restitution: self.restitution,
+            friction_combine: self.friction_combine,
+            restitution_combine: self.restitution_combine,
+        }
+    }
+}
+
+impl Physics {
+    /// Change the surface material of a collider
+    pub fn set_collider_material(&mut self, collider: ColliderHandle, material: PhysicsMaterial) {
+        if let Some(c) = self.collider_set.get_mut(collider.0) {
+            c.set_friction(material.friction);
+            c.set_restitution(material.restitution);
+            c.set_friction_combine_rule(material.friction_combine.into());
+            c.set_restitution_combine_rule(material.restitution_combine.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Transform, World};
+    use crate::physics::RigidBody;
+    use glam::Vec3;
+
+    /// Slide a box across the floor and return how far it got
+    fn slide(floor: PhysicsMaterial, block: PhysicsMaterial) -> f32 {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        world.spawn((
+            Transform::default(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::new(50.0, 0.5, 5.0)).with_material(floor),
+        ));
+        let block = world.spawn((
+            Transform::from_position(Vec3::new(0.0, 1.0, 0.0)),
+            RigidBody::dynamic(),
+            Collider::cuboid(Vec3::splat(0.5)).with_material(block),
+        ));
+        physics.step_world(&mut world, 1.0 / 60.0);
+        let body = physics.entity_body(block).unwrap();
+        physics.set_linear_velocity(body, Vec3::new(5.0, 0.0, 0.0));
+        for _ in 0..120 {
+            physics.step_world(&mut world, 1.0 / 60.0);
+        }
+        world.get::<Transform>(block).unwrap().position.x
+    }
+
+    #[test]
+    fn test_combine_rules_change_sliding() {
+        let rough = PhysicsMaterial::new(1.0, 0.0);
+        let ice = PhysicsMaterial::new(0.0, 0.0);
+        let average = slide(rough, ice);
+        let min = slide(rough, ice.with_friction_combine(CombineRule::Min));
+        let max = slide(rough, ice.with_friction_combine(CombineRule::Max));
+        assert!(min > average && average > max, "{min} {average} {max}");
+
+        let collider = Collider::ball(1.0).with_material(ice);
+        assert_eq!(collider.material(), ice);
+    }
+}
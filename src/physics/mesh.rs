@@ -0,0 +1,429 @@
+//! Collider shapes built from mesh data
+//!
+//! Anything implementing [`MeshSource`] — a renderer [`Mesh`] or loaded glTF
+//! data — can become a triangle mesh, a convex hull, or a convex
+//! decomposition for concave dynamic objects.
+
+use glam::{Quat, Vec3};
+use rapier3d::parry::transformation::vhacd::VHACDParameters;
+use rapier3d::prelude::SharedShape;
+
+use super::components::{Collider, ColliderShape, CompoundPart, to_point};
+use super::world::{ColliderHandle, Physics, RigidBodyHandle, isometry, rapier_to_quat};
+use crate::assets::{LoadedMesh, LoadedPrimitive};
+use crate::renderer::{Mesh, Vertex};
+
+/// Errors that can occur when building a collider from a mesh
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeshColliderError {
+    /// The mesh has no triangles
+    Empty,
+    /// An index points past the end of the vertex buffer
+    InvalidIndex(u32),
+    /// The points are all coplanar, so they enclose no volume
+    Degenerate,
+    /// A compound part is a triangle mesh, which can't be combined
+    CompositePart,
+}
+
+impl std::fmt::Display for MeshColliderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Mesh has no triangles"),
+            Self::InvalidIndex(i) => write!(f, "Invalid vertex index: {i}"),
+            Self::Degenerate => write!(f, "Mesh encloses no volume"),
+            Self::CompositePart => write!(f, "Triangle meshes can't be compound parts"),
+        }
+    }
+}
+
+impl std::error::Error for MeshColliderError {}
+
+/// Mesh data that colliders can be built from
+pub trait MeshSource {
+    /// Get the vertex positions and triangle indices
+    fn triangles(&self) -> (Vec<Vec3>, Vec<[u32; 3]>);
+}
+
+fn vertex_triangles(vertices: &[Vertex], indices: &[u32]) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    (
+        vertices.iter().map(|v| Vec3::from(v.position)).collect(),
+        indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect(),
+    )
+}
+
+impl MeshSource for Mesh {
+    fn triangles(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        vertex_triangles(&self.vertices, &self.indices)
+    }
+}
+
+impl MeshSource for LoadedPrimitive {
+    fn triangles(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        vertex_triangles(&self.vertices, &self.indices)
+    }
+}
+
+/// All primitives merged into one mesh
+impl MeshSource for LoadedMesh {
+    fn triangles(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for primitive in &self.primitives {
+            let base = vertices.len() as u32;
+            let (v, i) = primitive.triangles();
+            vertices.extend(v);
+            indices.extend(i.into_iter().map(|t| t.map(|i| i + base)));
+        }
+        (vertices, indices)
+    }
+}
+
+/// Get a mesh's triangles, checking that there are some and that indices are valid
+fn checked_triangles(
+    mesh: &impl MeshSource,
+) -> Result<(Vec<Vec3>, Vec<[u32; 3]>), MeshColliderError> {
+    let (vertices, indices) = mesh.triangles();
+    if indices.is_empty() {
+        return Err(MeshColliderError::Empty);
+    }
+    if let Some(&index) = indices
+        .iter()
+        .flatten()
+        .find(|&&i| i as usize >= vertices.len())
+    {
+        return Err(MeshColliderError::InvalidIndex(index));
+    }
+    Ok((vertices, indices))
+}
+
+/// Check that a compound has parts and none of them are triangle meshes
+fn check_compound_parts(parts: &[CompoundPart]) -> Result<(), MeshColliderError> {
+    if parts.is_empty() {
+        return Err(MeshColliderError::Empty);
+    }
+    for part in parts {
+        match &part.shape {
+            ColliderShape::TriMesh { .. } => return Err(MeshColliderError::CompositePart),
+            ColliderShape::Compound { parts } => check_compound_parts(parts)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Get the points of a convex shape, if it is one
+fn convex_points(shape: &SharedShape) -> Option<Vec<Vec3>> {
+    let polyhedron = shape.as_convex_polyhedron()?;
+    Some(
+        polyhedron
+            .points()
+            .iter()
+            .map(|p| Vec3::new(p.x, p.y, p.z))
+            .collect(),
+    )
+}
+
+impl ColliderShape {
+    /// Create a triangle mesh shape, for static level geometry
+    pub fn trimesh(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        let (vertices, indices) = checked_triangles(mesh)?;
+        Ok(Self::TriMesh { vertices, indices })
+    }
+
+    /// Create the convex hull of a mesh's vertices
+    pub fn convex_hull(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        let (vertices, _) = mesh.triangles();
+        Self::convex_hull_of(&vertices)
+    }
+
+    /// Create the convex hull of a set of points
+    pub fn convex_hull_of(points: &[Vec3]) -> Result<Self, MeshColliderError> {
+        if points.is_empty() {
+            return Err(MeshColliderError::Empty);
+        }
+        let points: Vec<_> = points.iter().map(|&p| to_point(p)).collect();
+        let hull = SharedShape::convex_hull(&points)
+            .filter(|hull| hull.mass_properties(1.0).mass() > f32::EPSILON)
+            .ok_or(MeshColliderError::Degenerate)?;
+        let points = convex_points(&hull).ok_or(MeshColliderError::Degenerate)?;
+        Ok(Self::ConvexHull { points })
+    }
+
+    /// Split a concave mesh into convex parts, for dynamic bodies
+    ///
+    /// This runs V-HACD and can take a while for large meshes, so build the
+    /// shape once and clone it.
+    pub fn convex_decomposition(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        Self::convex_decomposition_with_resolution(mesh, VHACDParameters::default().resolution)
+    }
+
+    /// Split a concave mesh into convex parts with a voxel resolution
+    ///
+    /// Higher resolutions follow the mesh more closely but are slower.
+    pub fn convex_decomposition_with_resolution(
+        mesh: &impl MeshSource,
+        resolution: u32,
+    ) -> Result<Self, MeshColliderError> {
+        let (vertices, indices) = checked_triangles(mesh)?;
+        let points: Vec<_> = vertices.iter().map(|&v| to_point(v)).collect();
+        let params = VHACDParameters {
+            resolution,
+            ..VHACDParameters::default()
+        };
+        let decomposition =
+            SharedShape::convex_decomposition_with_params(&points, &indices, &params);
+        let compound = decomposition
+            .as_compound()
+            .ok_or(MeshColliderError::Degenerate)?;
+
+        let parts: Vec<_> = compound
+            .shapes()
+            .iter()
+            .filter_map(|(position, shape)| {
+                let translation = position.translation.vector;
+                Some(
+                    CompoundPart::new(
+                        Self::ConvexHull {
+                            points: convex_points(shape)?,
+                        },
+                        Vec3::new(translation.x, translation.y, translation.z),
+                    )
+                    .with_rotation(rapier_to_quat(&position.rotation)),
+                )
+            })
+            .collect();
+        if parts.is_empty() {
+            return Err(MeshColliderError::Degenerate);
+        }
+        Ok(Self::Compound { parts })
+    }
+
+    /// Create a compound of several shapes
+    ///
+    /// Parts may be compounds themselves, but not triangle meshes.
+    pub fn compound(
+        parts: impl IntoIterator<Item = CompoundPart>,
+    ) -> Result<Self, MeshColliderError> {
+        let parts: Vec<_> = parts.into_iter().collect();
+        check_compound_parts(&parts)?;
+        Ok(Self::Compound { parts })
+    }
+}
+
+impl Collider {
+    /// Create a triangle mesh collider, for static level geometry
+    pub fn trimesh(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        ColliderShape::trimesh(mesh).map(Self::new)
+    }
+
+    /// Create a convex hull collider from a mesh's vertices
+    pub fn convex_hull(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        ColliderShape::convex_hull(mesh).map(Self::new)
+    }
+
+    /// Create a collider from the convex decomposition of a concave mesh
+    pub fn convex_decomposition(mesh: &impl MeshSource) -> Result<Self, MeshColliderError> {
+        ColliderShape::convex_decomposition(mesh).map(Self::new)
+    }
+
+    /// Create a compound collider
+    pub fn compound(
+        parts: impl IntoIterator<Item = CompoundPart>,
+    ) -> Result<Self, MeshColliderError> {
+        ColliderShape::compound(parts).map(Self::new)
+    }
+}
+
+impl Physics {
+    /// Add a collider of any shape to a rigid body
+    pub fn add_shape_collider(
+        &mut self,
+        body: RigidBodyHandle,
+        shape: &ColliderShape,
+        offset: Vec3,
+        rotation: Quat,
+        density: f32,
+    ) -> ColliderHandle {
+        let collider = Collider::new(shape.clone())
+            .with_density(density)
+            .builder()
+            .position(isometry(offset, rotation))
+            .build();
+        ColliderHandle(self.collider_set.insert_with_parent(
+            collider,
+            body.0,
+            &mut self.rigid_body_set,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An L-shaped mesh made of two unit cubes
+    fn l_shape() -> LoadedMesh {
+        let cube = Mesh::cube();
+        let shifted = |offset: Vec3| LoadedPrimitive {
+            vertices: cube
+                .vertices
+                .iter()
+                .map(|v| {
+                    let mut v = *v;
+                    v.position = (Vec3::from(v.position) + offset).into();
+                    v
+                })
+                .collect(),
+            indices: cube.indices.clone(),
+            material_index: None,
+        };
+        LoadedMesh {
+            name: "L".to_string(),
+            primitives: vec![shifted(Vec3::ZERO), shifted(Vec3::new(1.0, 0.0, 0.0))],
+        }
+    }
+
+    #[test]
+    fn test_shapes_from_meshes() {
+        let cube = Mesh::cube();
+        let ColliderShape::TriMesh { vertices, indices } = ColliderShape::trimesh(&cube).unwrap()
+        else {
+            panic!("expected a triangle mesh");
+        };
+        assert_eq!(vertices.len(), cube.vertices.len());
+        assert_eq!(indices.len() * 3, cube.indices.len());
+
+        let ColliderShape::ConvexHull { points } = ColliderShape::convex_hull(&cube).unwrap()
+        else {
+            panic!("expected a convex hull");
+        };
+        assert_eq!(points.len(), 8);
+
+        let ColliderShape::TriMesh { indices, .. } = ColliderShape::trimesh(&l_shape()).unwrap()
+        else {
+            panic!("expected a triangle mesh");
+        };
+        assert_eq!(indices.len(), 24);
+        assert!(indices.iter().flatten().any(|&i| i >= 24));
+
+        assert_eq!(
+            ColliderShape::trimesh(&Mesh::new()),
+            Err(MeshColliderError::Empty)
+        );
+        let flat = [Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::ONE.with_y(0.0)];
+        assert_eq!(
+            ColliderShape::convex_hull_of(&flat),
+            Err(MeshColliderError::Degenerate)
+        );
+        let broken = Mesh::from_data(cube.vertices.clone(), vec![0, 1, 99]);
+        assert_eq!(
+            ColliderShape::trimesh(&broken),
+            Err(MeshColliderError::InvalidIndex(99))
+        );
+    }
+
+    #[test]
+    fn test_invalid_trimesh_falls_back_to_empty_shape() {
+        use crate::ecs::{Transform, World};
+        use crate::physics::RigidBody;
+
+        let empty = ColliderShape::TriMesh {
+            vertices: vec![Vec3::ZERO, Vec3::X, Vec3::Z],
+            indices: Vec::new(),
+        };
+        let out_of_range = ColliderShape::TriMesh {
+            vertices: vec![Vec3::ZERO, Vec3::X, Vec3::Z],
+            indices: vec![[0, 1, 3]],
+        };
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        for shape in [empty, out_of_range] {
+            assert!(shape.shared_shape().as_ball().is_some());
+            world.spawn((
+                Transform::default(),
+                RigidBody::fixed(),
+                Collider::new(shape),
+            ));
+        }
+        physics.step_world(&mut world, 1.0 / 60.0);
+        assert_eq!(physics.body_count(), 2);
+    }
+
+    #[test]
+    fn test_invalid_compounds() {
+        let ball = || CompoundPart::new(ColliderShape::Ball { radius: 0.5 }, Vec3::ZERO);
+        let cube = ColliderShape::trimesh(&Mesh::cube()).unwrap();
+        assert_eq!(ColliderShape::compound([]), Err(MeshColliderError::Empty));
+        assert_eq!(
+            ColliderShape::compound([ball(), CompoundPart::new(cube.clone(), Vec3::ZERO)]),
+            Err(MeshColliderError::CompositePart)
+        );
+
+        // Nested compounds are flattened, with offsets combined
+        let inner = ColliderShape::compound([ball()]).unwrap();
+        let nested = ColliderShape::compound([CompoundPart::new(inner, Vec3::X)]).unwrap();
+        let shape = nested.shared_shape();
+        let parts = shape.as_compound().unwrap().shapes();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].0.translation.vector.x, 1.0);
+
+        // Invalid compounds built directly fall back instead of panicking
+        let empty = ColliderShape::Compound { parts: Vec::new() };
+        let meshes = ColliderShape::Compound {
+            parts: vec![CompoundPart::new(cube, Vec3::ZERO)],
+        };
+        let mut physics = Physics::new();
+        let body = physics.create_dynamic_body(Vec3::ZERO, Quat::IDENTITY);
+        for shape in [&empty, &meshes, &nested] {
+            physics.add_shape_collider(body, shape, Vec3::ZERO, Quat::IDENTITY, 1.0);
+        }
+        physics.step(1.0 / 60.0);
+        assert!(empty.shared_shape().as_ball().is_some());
+        assert!(meshes.shared_shape().as_ball().is_some());
+    }
+
+    #[test]
+    fn test_decomposed_and_compound_bodies_collide() {
+        let shape = ColliderShape::convex_decomposition_with_resolution(&l_shape(), 32).unwrap();
+        let ColliderShape::Compound { parts } = &shape else {
+            panic!("expected a compound");
+        };
+        assert!(!parts.is_empty());
+
+        let mut physics = Physics::new();
+        let ground = physics.create_static_body(Vec3::ZERO, Quat::IDENTITY);
+        let floor = ColliderShape::trimesh(&Mesh::plane(20.0)).unwrap();
+        physics.add_shape_collider(ground, &floor, Vec3::ZERO, Quat::IDENTITY, 1.0);
+
+        let dumbbell = ColliderShape::compound([
+            CompoundPart::new(
+                ColliderShape::Ball { radius: 0.5 },
+                Vec3::new(-1.0, 0.0, 0.0),
+            ),
+            CompoundPart::new(
+                ColliderShape::Ball { radius: 0.5 },
+                Vec3::new(1.0, 0.0, 0.0),
+            ),
+        ])
+        .unwrap();
+        let mut bodies = Vec::new();
+        for (i, shape) in [shape, dumbbell].iter().enumerate() {
+            let body =
+                physics.create_dynamic_body(Vec3::new(i as f32 * 5.0, 2.0, 0.0), Quat::IDENTITY);
+            physics.add_shape_collider(body, shape, Vec3::ZERO, Quat::IDENTITY, 1.0);
+            bodies.push(body);
+        }
+        for _ in 0..180 {
+            physics.step(1.0 / 60.0);
+        }
+
+        for body in bodies {
+            let y = physics.get_position(body).unwrap().y;
+            assert!(y > 0.3 && y < 0.8, "body rests on the floor, y = {y}");
+        }
+    }
+}
@@ -2,6 +2,27 @@
 //!
 //! Built on top of rapier3d
 
+mod character;
+mod components;
+mod config;
+mod events;
+mod joints;
+mod materials;
+mod mesh;
+mod query;
+mod sensors;
+mod sync;
 mod world;
 
-pub use world::{ColliderHandle, Physics, RaycastHit, RigidBodyHandle};
+pub use character::{Autostep, CharacterCollision, CharacterController, CharacterMovement};
+pub use components::{
+    BodyType, Collider, ColliderShape, CompoundPart, RigidBody, register_physics_components,
+};
+pub use config::{CollisionLayers, MAX_COLLISION_LAYERS, PhysicsConfig, PhysicsConfigError};
+pub use events::{CollisionEvent, CollisionEventKind, ContactForceEvent, ContactPoint};
+pub use joints::{Joint, JointBroken, JointHandle, JointMotor, JointState, JointType};
+pub use materials::{CombineRule, PhysicsMaterial};
+pub use mesh::{MeshColliderError, MeshSource};
+pub use query::{FeatureId, QueryFilter, RaycastHit, ShapeCastHit};
+pub use sensors::{SensorEvent, SensorEventKind};
+pub use world::{ColliderHandle, Physics, RigidBodyHandle};
@@ -0,0 +1,505 @@
+//! Scene queries: raycasts, shape casts and overlap tests
+//!
+//! Queries run against the collider positions of the last
+//! [`Physics::step`], and can be narrowed down with a [`QueryFilter`].
+//!
+//! Apart from [`Physics::raycast`], ray and shape cast directions are
+//! normalized, so `max_distance` and hit distances are in world units.
+
+use glam::{Quat, Vec3};
+use hecs::Entity;
+use rapier3d::na::Vector3;
+use rapier3d::parry::query::ShapeCastOptions;
+use rapier3d::prelude::{
+    Collider as RapierCollider, ColliderHandle as RapierColliderHandle,
+    FeatureId as RapierFeatureId, Group, InteractionGroups, QueryFilter as RapierQueryFilter,
+    QueryFilterFlags, Ray,
+};
+
+use super::components::{ColliderShape, to_point};
+use super::sync::user_data_to_entity;
+use super::world::{ColliderHandle, Physics, RigidBodyHandle, isometry};
+
+/// Feature of a shape that a query hit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FeatureId {
+    /// A vertex, by shape-dependent index
+    Vertex(u32),
+    /// An edge, by shape-dependent index
+    Edge(u32),
+    /// A face, by shape-dependent index (the triangle index for meshes)
+    Face(u32),
+    /// The shape doesn't report features
+    #[default]
+    Unknown,
+}
+
+impl From<RapierFeatureId> for FeatureId {
+    fn from(feature: RapierFeatureId) -> Self {
+        match feature {
+            RapierFeatureId::Vertex(i) => Self::Vertex(i),
+            RapierFeatureId::Edge(i) => Self::Edge(i),
+            RapierFeatureId::Face(i) => Self::Face(i),
+            RapierFeatureId::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// Which colliders a query considers
+///
+/// ```ignore
+/// let ignore_player = |_, entity: Option<Entity>| entity != Some(player);
+/// let filter = QueryFilter::new()
+///     .with_groups(0b0001, 0b0110)
+///     .exclude_sensors()
+///     .with_predicate(&ignore_player);
+/// let hits = physics.raycast_all(eye, forward, 100.0, &filter);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct QueryFilter<'a> {
+    /// Collision groups as `(memberships, filter)` bit masks
+    pub groups: Option<(u32, u32)>,
+    /// Bodies whose colliders are skipped
+    pub exclude_bodies: &'a [RigidBodyHandle],
+    /// Colliders that are skipped
+    pub exclude_colliders: &'a [ColliderHandle],
+    /// Skip sensor colliders
+    pub exclude_sensors: bool,
+    /// Skip colliders that aren't sensors
+    pub exclude_solids: bool,
+    /// Custom test, given the collider and its entity if it has one
+    pub predicate: Option<&'a dyn Fn(ColliderHandle, Option<Entity>) -> bool>,
+}
+
+impl<'a> QueryFilter<'a> {
+    /// Create a filter that accepts every collider
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only consider colliders whose groups interact with these
+    #[must_use]
+    pub fn with_groups(mut self, memberships: u32, filter: u32) -> Self {
+        self.groups = Some((memberships, filter));
+        self
+    }
+
+    /// Skip the colliders of these bodies
+    #[must_use]
+    pub fn exclude_bodies(mut self, bodies: &'a [RigidBodyHandle]) -> Self {
+        self.exclude_bodies = bodies;
+        self
+    }
+
+    /// Skip these colliders
+    #[must_use]
+    pub fn exclude_colliders(mut self, colliders: &'a [ColliderHandle]) -> Self {
+        self.exclude_colliders = colliders;
+        self
+    }
+
+    /// Skip sensor colliders
+    #[must_use]
+    pub fn exclude_sensors(mut self) -> Self {
+        self.exclude_sensors = true;
+        self
+    }
+
+    /// Only consider sensor colliders
+    #[must_use]
+    pub fn only_sensors(mut self) -> Self {
+        self.exclude_solids = true;
+        self
+    }
+
+    /// Skip colliders for which `predicate` returns false
+    #[must_use]
+    pub fn with_predicate(
+        mut self,
+        predicate: &'a dyn Fn(ColliderHandle, Option<Entity>) -> bool,
+    ) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Check the parts rapier can't express: body lists and the predicate
+    fn accepts(&self, handle: RapierColliderHandle, collider: &RapierCollider) -> bool {
+        let excluded_body = collider
+            .parent()
+            .is_some_and(|parent| self.exclude_bodies.iter().any(|b| b.0 == parent));
+        let handle = ColliderHandle(handle);
+        !excluded_body
+            && !self.exclude_colliders.contains(&handle)
+            && self
+                .predicate
+                .is_none_or(|predicate| predicate(handle, user_data_to_entity(collider.user_data)))
+    }
+
+    /// Run `query` with the equivalent rapier filter
+    fn with_rapier<R>(&self, query: impl FnOnce(RapierQueryFilter) -> R) -> R {
+        let mut flags = QueryFilterFlags::empty();
+        if self.exclude_sensors {
+            flags |= QueryFilterFlags::EXCLUDE_SENSORS;
+        }
+        if self.exclude_solids {
+            flags |= QueryFilterFlags::EXCLUDE_SOLIDS;
+        }
+        let predicate = |handle, collider: &RapierCollider| self.accepts(handle, collider);
+        let mut filter = RapierQueryFilter::new().predicate(&predicate);
+        filter.flags = flags;
+        if let Some((memberships, filter_bits)) = self.groups {
+            filter.groups = Some(InteractionGroups::new(
+                Group::from_bits_truncate(memberships),
+                Group::from_bits_truncate(filter_bits),
+            ));
+        }
+        query(filter)
+    }
+}
+
+impl std::fmt::Debug for QueryFilter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryFilter")
+            .field("groups", &self.groups)
+            .field("exclude_bodies", &self.exclude_bodies)
+            .field("exclude_colliders", &self.exclude_colliders)
+            .field("exclude_sensors", &self.exclude_sensors)
+            .field("exclude_solids", &self.exclude_solids)
+            .field("predicate", &self.predicate.is_some())
+            .finish()
+    }
+}
+
+/// Result of a raycast
+#[derive(Debug, Clone)]
+pub struct RaycastHit {
+    /// The collider that was hit
+    pub collider: ColliderHandle,
+    /// Entity of the collider, for colliders created from components
+    pub entity: Option<Entity>,
+    /// The point of intersection
+    pub point: Vec3,
+    /// Surface normal at the point of intersection
+    pub normal: Vec3,
+    /// Distance from ray origin
+    pub distance: f32,
+    /// Feature of the shape that was hit
+    pub feature: FeatureId,
+}
+
+/// Result of a shape cast
+#[derive(Debug, Clone)]
+pub struct ShapeCastHit {
+    /// The collider that was hit
+    pub collider: ColliderHandle,
+    /// Entity of the collider, for colliders created from components
+    pub entity: Option<Entity>,
+    /// Distance the shape traveled before touching the collider
+    pub distance: f32,
+    /// Contact point on the collider
+    pub point: Vec3,
+    /// Surface normal of the collider at the contact point
+    pub normal: Vec3,
+    /// Whether the shape was already touching the collider at its start position
+    pub penetrating: bool,
+}
+
+fn to_vec3(v: &Vector3<f32>) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+fn ray(origin: Vec3, direction: Vec3) -> Ray {
+    Ray::new(
+        to_point(origin),
+        Vector3::new(direction.x, direction.y, direction.z),
+    )
+}
+
+impl Physics {
+    /// Cast a ray and return the first hit
+    ///
+    /// `max_distance` and [`RaycastHit::distance`] are in multiples of the
+    /// length of `direction`.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RaycastHit> {
+        self.cast_ray(
+            &ray(origin, direction),
+            max_distance,
+            &QueryFilter::default(),
+        )
+    }
+
+    /// Cast a ray and return the first hit accepted by `filter`
+    ///
+    /// `direction` is normalized, so distances are in world units.
+    pub fn raycast_filtered(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<RaycastHit> {
+        let ray = ray(origin, direction.normalize_or_zero());
+        self.cast_ray(&ray, max_distance, filter)
+    }
+
+    fn cast_ray(&self, ray: &Ray, max_distance: f32, filter: &QueryFilter) -> Option<RaycastHit> {
+        let (handle, hit) = filter.with_rapier(|filter| {
+            self.query_pipeline.cast_ray_and_get_normal(
+                &self.rigid_body_set,
+                &self.collider_set,
+                ray,
+                max_distance,
+                true,
+                filter,
+            )
+        })?;
+        Some(RaycastHit {
+            collider: ColliderHandle(handle),
+            entity: self.collider_entity(ColliderHandle(handle)),
+            point: to_vec3(&ray.point_at(hit.time_of_impact).coords),
+            normal: to_vec3(&hit.normal),
+            distance: hit.time_of_impact,
+            feature: hit.feature.into(),
+        })
+    }
+
+    /// Cast a ray and return every hit accepted by `filter`, nearest first
+    ///
+    /// `direction` is normalized, so distances are in world units.
+    pub fn raycast_all(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Vec<RaycastHit> {
+        let ray = ray(origin, direction.normalize_or_zero());
+        let mut hits = Vec::new();
+        filter.with_rapier(|filter| {
+            self.query_pipeline.intersections_with_ray(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &ray,
+                max_distance,
+                true,
+                filter,
+                |handle, hit| {
+                    hits.push(RaycastHit {
+                        collider: ColliderHandle(handle),
+                        entity: self.collider_entity(ColliderHandle(handle)),
+                        point: to_vec3(&ray.point_at(hit.time_of_impact).coords),
+                        normal: to_vec3(&hit.normal),
+                        distance: hit.time_of_impact,
+                        feature: hit.feature.into(),
+                    });
+                    true
+                },
+            );
+        });
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Sweep a shape along `direction` and return the first collider it touches
+    pub fn cast_shape(
+        &self,
+        shape: &ColliderShape,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<ShapeCastHit> {
+        let shape = shape.shared_shape();
+        let direction = direction.normalize_or_zero();
+        let options = ShapeCastOptions {
+            compute_impact_geometry_on_penetration: true,
+            ..ShapeCastOptions::with_max_time_of_impact(max_distance)
+        };
+        let (handle, hit) = filter.with_rapier(|filter| {
+            self.query_pipeline.cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &isometry(position, rotation),
+                &Vector3::new(direction.x, direction.y, direction.z),
+                shape.as_ref(),
+                options,
+                filter,
+            )
+        })?;
+        Some(ShapeCastHit {
+            collider: ColliderHandle(handle),
+            entity: self.collider_entity(ColliderHandle(handle)),
+            distance: hit.time_of_impact,
+            point: to_vec3(&hit.witness1.coords),
+            normal: to_vec3(&hit.normal1),
+            penetrating: hit.time_of_impact == 0.0,
+        })
+    }
+
+    /// Get every collider containing a point
+    pub fn overlap_point(&self, point: Vec3, filter: &QueryFilter) -> Vec<ColliderHandle> {
+        let mut colliders = Vec::new();
+        filter.with_rapier(|filter| {
+            self.query_pipeline.intersections_with_point(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &to_point(point),
+                filter,
+                |handle| {
+                    colliders.push(ColliderHandle(handle));
+                    true
+                },
+            );
+        });
+        colliders
+    }
+
+    /// Get every collider overlapping a shape
+    pub fn overlap_shape(
+        &self,
+        shape: &ColliderShape,
+        position: Vec3,
+        rotation: Quat,
+        filter: &QueryFilter,
+    ) -> Vec<ColliderHandle> {
+        let shape = shape.shared_shape();
+        let mut colliders = Vec::new();
+        filter.with_rapier(|filter| {
+            self.query_pipeline.intersections_with_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &isometry(position, rotation),
+                shape.as_ref(),
+                filter,
+                |handle| {
+                    colliders.push(ColliderHandle(handle));
+                    true
+                },
+            );
+        });
+        colliders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three unit boxes along +X at x = 2, 4 and 6, the middle one a sensor
+    fn boxes() -> (Physics, [RigidBodyHandle; 3], [ColliderHandle; 3]) {
+        let mut physics = Physics::new();
+        let mut bodies = Vec::new();
+        let mut colliders = Vec::new();
+        for i in 0..3 {
+            let body = physics
+                .create_static_body(Vec3::new(2.0 + 2.0 * i as f32, 0.0, 0.0), Quat::IDENTITY);
+            let collider = physics.add_box_collider(body, Vec3::splat(0.5), 1.0);
+            bodies.push(body);
+            colliders.push(collider);
+        }
+        physics
+            .collider_set
+            .get_mut(colliders[1].0)
+            .unwrap()
+            .set_sensor(true);
+        physics.step(1.0 / 60.0);
+        (
+            physics,
+            bodies.try_into().unwrap(),
+            colliders.try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_raycasts_with_filters() {
+        let (physics, bodies, colliders) = boxes();
+
+        // Plain raycasts measure in multiples of the direction's length
+        let hit = physics.raycast(Vec3::ZERO, Vec3::X * 5.0, 1.0).unwrap();
+        assert_eq!(hit.collider, colliders[0]);
+        assert!((hit.distance - 0.3).abs() < 1e-4);
+        assert!((hit.point.x - 1.5).abs() < 1e-4);
+
+        let hit = physics
+            .raycast_filtered(Vec3::ZERO, Vec3::X * 5.0, 10.0, &QueryFilter::new())
+            .unwrap();
+        assert!((hit.distance - 1.5).abs() < 1e-4);
+        assert_eq!(hit.normal, Vec3::NEG_X);
+        assert!(matches!(hit.feature, FeatureId::Face(_)));
+
+        let all = physics.raycast_all(Vec3::ZERO, Vec3::X, 10.0, &QueryFilter::new());
+        let hit_colliders: Vec<_> = all.iter().map(|h| h.collider).collect();
+        assert_eq!(hit_colliders, colliders);
+
+        let filter = QueryFilter::new()
+            .exclude_sensors()
+            .exclude_bodies(&bodies[..1]);
+        let hit = physics.raycast_filtered(Vec3::ZERO, Vec3::X, 10.0, &filter);
+        assert_eq!(hit.map(|h| h.collider), Some(colliders[2]));
+
+        let last_only = |collider, _| collider == colliders[2];
+        let filter = QueryFilter::new().with_predicate(&last_only);
+        let all = physics.raycast_all(Vec3::ZERO, Vec3::X, 10.0, &filter);
+        assert_eq!(all.len(), 1);
+
+        let filter = QueryFilter::new().with_groups(u32::MAX, 0);
+        assert!(
+            physics
+                .raycast_filtered(Vec3::ZERO, Vec3::X, 10.0, &filter)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_shape_casts_and_overlaps() {
+        let (physics, _, colliders) = boxes();
+        let ball = ColliderShape::Ball { radius: 0.25 };
+
+        let hit = physics
+            .cast_shape(
+                &ball,
+                Vec3::ZERO,
+                Quat::IDENTITY,
+                Vec3::X,
+                10.0,
+                &QueryFilter::new(),
+            )
+            .unwrap();
+        assert_eq!(hit.collider, colliders[0]);
+        assert!(
+            (hit.distance - 1.25).abs() < 1e-3,
+            "distance {}",
+            hit.distance
+        );
+        assert!((hit.point.x - 1.5).abs() < 1e-3);
+        assert!(hit.normal.x < -0.99);
+        assert!(!hit.penetrating);
+
+        assert_eq!(
+            physics.overlap_point(Vec3::new(4.2, 0.0, 0.0), &QueryFilter::new()),
+            [colliders[1]]
+        );
+        assert!(
+            physics
+                .overlap_point(
+                    Vec3::new(4.2, 0.0, 0.0),
+                    &QueryFilter::new().exclude_sensors()
+                )
+                .is_empty()
+        );
+
+        let wide = ColliderShape::Cuboid {
+            half_extents: Vec3::new(2.0, 0.5, 0.5),
+        };
+        let mut overlapping = physics.overlap_shape(
+            &wide,
+            Vec3::new(3.0, 0.0, 0.0),
+            Quat::IDENTITY,
+            &QueryFilter::new(),
+        );
+        overlapping.sort_by_key(|c| c.0.into_raw_parts());
+        assert_eq!(overlapping, colliders[..2]);
+    }
+}
@@ -0,0 +1,260 @@
+//! Trigger volumes
+//!
+//! Sensor colliders detect overlaps without pushing anything. After each
+//! [`Physics::step`] the overlaps of every sensor are compared with the
+//! previous step to produce enter, stay and exit events.
+
+use glam::{Quat, Vec3};
+use hecs::Entity;
+use rapier3d::prelude::{ActiveCollisionTypes, ColliderHandle as RapierColliderHandle};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::components::{Collider, ColliderShape};
+use super::sync::user_data_to_entity;
+use super::world::{ColliderHandle, Physics, RigidBodyHandle, isometry};
+
+/// Change in a sensor overlap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SensorEventKind {
+    /// The collider started overlapping the sensor this step
+    Enter,
+    /// The collider was already overlapping the sensor and still is
+    Stay,
+    /// The collider stopped overlapping the sensor, or one of them was removed
+    Exit,
+}
+
+/// A collider entering, staying in or leaving a sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorEvent {
+    /// Enter, stay or exit
+    pub kind: SensorEventKind,
+    /// The sensor collider
+    pub sensor: ColliderHandle,
+    /// The overlapping collider
+    pub other: ColliderHandle,
+    /// Entity of the sensor, for colliders created from components
+    pub sensor_entity: Option<Entity>,
+    /// Entity of the overlapping collider, for colliders created from components
+    pub other_entity: Option<Entity>,
+}
+
+/// Overlapping colliders of each sensor as of the last step
+pub(super) type SensorOverlaps = FxHashMap<RapierColliderHandle, FxHashSet<RapierColliderHandle>>;
+
+/// Collision types for sensors, so fixed trigger zones also see kinematic characters
+pub(super) fn sensor_collision_types() -> ActiveCollisionTypes {
+    ActiveCollisionTypes::all()
+}
+
+impl Physics {
+    /// Add a sensor collider to a rigid body
+    ///
+    /// Use a fixed body for trigger zones such as checkpoints.
+    pub fn add_sensor_collider(
+        &mut self,
+        body: RigidBodyHandle,
+        shape: &ColliderShape,
+        offset: Vec3,
+        rotation: Quat,
+    ) -> ColliderHandle {
+        let collider = Collider::new(shape.clone())
+            .sensor()
+            .builder()
+            .position(isometry(offset, rotation))
+            .build();
+        ColliderHandle(self.collider_set.insert_with_parent(
+            collider,
+            body.0,
+            &mut self.rigid_body_set,
+        ))
+    }
+
+    /// Get the colliders overlapping a sensor as of the last step
+    pub fn sensor_overlaps(&self, sensor: ColliderHandle) -> Vec<ColliderHandle> {
+        self.sensor_overlaps
+            .get(&sensor.0)
+            .map(|others| others.iter().map(|&h| ColliderHandle(h)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Check if a collider overlapped a sensor in the last step
+    pub fn is_in_sensor(&self, sensor: ColliderHandle, collider: ColliderHandle) -> bool {
+        self.sensor_overlaps
+            .get(&sensor.0)
+            .is_some_and(|others| others.contains(&collider.0))
+    }
+
+    /// Get the entities overlapping the sensor colliders of an entity
+    pub fn sensor_entities(&self, entity: Entity) -> Vec<Entity> {
+        let Some(rb) = self
+            .entity_body(entity)
+            .and_then(|body| self.rigid_body_set.get(body.0))
+        else {
+            return Vec::new();
+        };
+        let mut entities: Vec<Entity> = rb
+            .colliders()
+            .iter()
+            .filter_map(|sensor| self.sensor_overlaps.get(sensor))
+            .flatten()
+            .filter_map(|&other| self.collider_entity(ColliderHandle(other)))
+            .collect();
+        entities.sort_unstable_by_key(|e| e.to_bits());
+        entities.dedup();
+        entities
+    }
+
+    /// Take the sensor events produced since the last call
+    pub fn drain_sensor_events(&mut self) -> Vec<SensorEvent> {
+        std::mem::take(&mut self.sensor_events)
+    }
+
+    /// Compare sensor overlaps with the previous step and record events
+    pub(super) fn update_sensors(&mut self) {
+        let mut current = SensorOverlaps::default();
+        for (handle, collider) in self.collider_set.iter() {
+            if !collider.is_sensor() {
+                continue;
+            }
+            let others: FxHashSet<_> = self
+                .narrow_phase
+                .intersection_pairs_with(handle)
+                .filter(|&(_, _, intersecting)| intersecting)
+                .map(|(a, b, _)| if a == handle { b } else { a })
+                .collect();
+            if !others.is_empty() {
+                current.insert(handle, others);
+            }
+        }
+
+        let previous = std::mem::take(&mut self.sensor_overlaps);
+        let mut events = Vec::new();
+        for (&sensor, others) in &current {
+            let before = previous.get(&sensor);
+            for &other in others {
+                let kind = if before.is_some_and(|b| b.contains(&other)) {
+                    SensorEventKind::Stay
+                } else {
+                    SensorEventKind::Enter
+                };
+                events.push(self.sensor_event(kind, sensor, other));
+            }
+        }
+        for (&sensor, others) in &previous {
+            let now = current.get(&sensor);
+            for &other in others {
+                if !now.is_some_and(|n| n.contains(&other)) {
+                    events.push(self.sensor_event(SensorEventKind::Exit, sensor, other));
+                }
+            }
+        }
+
+        self.sensor_events.extend(events);
+        self.sensor_overlaps = current;
+    }
+
+    fn sensor_event(
+        &self,
+        kind: SensorEventKind,
+        sensor: RapierColliderHandle,
+        other: RapierColliderHandle,
+    ) -> SensorEvent {
+        let entity = |handle| {
+            self.collider_set
+                .get(handle)
+                .and_then(|c| user_data_to_entity(c.user_data))
+        };
+        SensorEvent {
+            kind,
+            sensor: ColliderHandle(sensor),
+            other: ColliderHandle(other),
+            sensor_entity: entity(sensor),
+            other_entity: entity(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Transform, World};
+    use crate::physics::RigidBody;
+
+    fn kinds(events: &[SensorEvent]) -> Vec<SensorEventKind> {
+        events.iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn test_enter_stay_exit() {
+        let mut physics = Physics::new();
+        let zone = physics.create_static_body(Vec3::ZERO, Quat::IDENTITY);
+        let sensor = physics.add_sensor_collider(
+            zone,
+            &ColliderShape::Cuboid {
+                half_extents: Vec3::ONE,
+            },
+            Vec3::ZERO,
+            Quat::IDENTITY,
+        );
+        let mover = physics.create_kinematic_body(Vec3::new(-3.0, 0.0, 0.0), Quat::IDENTITY);
+        let collider = physics.add_sphere_collider(mover, 0.5, 1.0);
+
+        let step_to = |physics: &mut Physics, x: f32| {
+            physics.set_kinematic_position(mover, Vec3::new(x, 0.0, 0.0));
+            physics.step(1.0 / 60.0);
+            physics.step(1.0 / 60.0);
+            physics.drain_sensor_events()
+        };
+
+        assert!(step_to(&mut physics, -3.0).is_empty());
+        let events = step_to(&mut physics, 0.0);
+        assert_eq!(
+            kinds(&events),
+            [SensorEventKind::Enter, SensorEventKind::Stay]
+        );
+        assert_eq!((events[0].sensor, events[0].other), (sensor, collider));
+        assert!(physics.is_in_sensor(sensor, collider));
+        assert_eq!(physics.sensor_overlaps(sensor), [collider]);
+
+        let events = step_to(&mut physics, 3.0);
+        assert_eq!(kinds(&events), [SensorEventKind::Exit]);
+        assert!(physics.sensor_overlaps(sensor).is_empty());
+
+        // Removing an overlapping body counts as leaving
+        step_to(&mut physics, 0.0);
+        physics.remove_body(mover);
+        physics.step(1.0 / 60.0);
+        assert_eq!(
+            kinds(&physics.drain_sensor_events()),
+            [SensorEventKind::Exit]
+        );
+    }
+
+    #[test]
+    fn test_sensor_events_map_to_entities() {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        let checkpoint = world.spawn((
+            Transform::default(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::splat(2.0)).sensor(),
+        ));
+        let player = world.spawn((
+            Transform::from_position(Vec3::new(0.0, 1.0, 0.0)),
+            RigidBody::kinematic(),
+            Collider::capsule(0.5, 0.5),
+        ));
+        physics.step_world(&mut world, 1.0 / 60.0);
+        physics.step_world(&mut world, 1.0 / 60.0);
+
+        let events = physics.drain_sensor_events();
+        let enter = events
+            .iter()
+            .find(|e| e.kind == SensorEventKind::Enter)
+            .expect("player entered the checkpoint");
+        assert_eq!(enter.sensor_entity, Some(checkpoint));
+        assert_eq!(enter.other_entity, Some(player));
+        assert_eq!(physics.sensor_entities(checkpoint), [player]);
+    }
+}
@@ -0,0 +1,288 @@
+//! Synchronization between ECS entities and the physics world
+//!
+//! Entities with a [`Transform`] and a [`RigidBody`] get a rapier body (plus a
+//! collider if they have a [`Collider`]) on the next step. Dynamic bodies
+//! write their pose back to the transform; kinematic bodies follow it.
+
+use glam::{Quat, Vec3};
+use hecs::Entity;
+use rapier3d::na::Vector3;
+
+use super::components::{BodyType, Collider, RigidBody};
+use super::world::{ColliderHandle, Physics, RigidBodyHandle, isometry, rapier_to_quat};
+use crate::ecs::{Disabled, PhysicsDriven, Transform, Velocity, World};
+
+/// A rapier body created for an entity
+#[derive(Debug, Clone, Copy)]
+pub(super) struct SyncedBody {
+    handle: RigidBodyHandle,
+    body_type: BodyType,
+    /// Pose last written to or read from the transform
+    pose: (Vec3, Quat),
+}
+
+/// Store an entity in rapier user data
+fn entity_to_user_data(entity: Entity) -> u128 {
+    u128::from(entity.to_bits().get())
+}
+
+/// Read an entity from rapier user data
+pub(super) fn user_data_to_entity(user_data: u128) -> Option<Entity> {
+    u64::try_from(user_data).ok().and_then(Entity::from_bits)
+}
+
+impl Physics {
+    /// Sync the world into the simulation, step it, and write the results back
+    ///
+    /// Physics entities are expected to be hierarchy roots, since their
+    /// [`Transform`] is treated as a world-space pose. The engine calls this
+    /// on the fixed timestep for [`EngineContext::physics`](crate::core::EngineContext::physics).
+    pub fn step_world(&mut self, world: &mut World, dt: f32) {
+        self.sync_to_physics(world);
+        self.step(dt);
+        self.sync_from_physics(world);
+    }
+
+    /// Create, remove and move bodies to match the world
+    ///
+    /// Bodies are created for new [`RigidBody`] entities, starting with their
+    /// [`Velocity`] if they have one, and removed for
+    /// despawned or [`Disabled`] ones. Kinematic bodies are moved to their
+    /// transform; other bodies are teleported if their transform was changed
+    /// outside the simulation.
+    pub fn sync_to_physics(&mut self, world: &mut World) {
+        let stale: Vec<Entity> = self
+            .synced
+            .keys()
+            .copied()
+            .filter(|&entity| {
+                !world.contains(entity)
+                    || world.get::<RigidBody>(entity).is_err()
+                    || world.get::<Disabled>(entity).is_ok()
+            })
+            .collect();
+        for entity in stale {
+            self.remove_entity(world, entity);
+        }
+
+        let mut created = Vec::new();
+        for (entity, (transform, body, collider, velocity)) in world
+            .query::<(&Transform, &RigidBody, Option<&Collider>, Option<&Velocity>)>()
+            .without::<&Disabled>()
+            .iter()
+        {
+            if self.synced.contains_key(&entity) {
+                continue;
+            }
+            let (linvel, angvel) =
+                velocity.map_or((Vec3::ZERO, Vec3::ZERO), |v| (v.linear, v.angular));
+            let rigid_body = body
+                .builder()
+                .position(isometry(transform.position, transform.rotation))
+                .linvel(Vector3::new(linvel.x, linvel.y, linvel.z))
+                .angvel(Vector3::new(angvel.x, angvel.y, angvel.z))
+                .user_data(entity_to_user_data(entity))
+                .build();
+            let handle = self.rigid_body_set.insert(rigid_body);
+            if let Some(collider) = collider {
+                let groups = self.layer_groups(collider.layer.as_deref());
+                let collider = collider
+                    .builder()
+                    .collision_groups(groups)
+                    .solver_groups(groups)
+                    .user_data(entity_to_user_data(entity))
+                    .build();
+                self.collider_set
+                    .insert_with_parent(collider, handle, &mut self.rigid_body_set);
+            }
+
+            let handle = RigidBodyHandle(handle);
+            self.synced.insert(
+                entity,
+                SyncedBody {
+                    handle,
+                    body_type: body.body_type,
+                    pose: (transform.position, transform.rotation),
+                },
+            );
+            created.push((entity, handle, body.body_type));
+        }
+        for (entity, handle, body_type) in created {
+            let _ = world.insert_one(entity, handle);
+            if body_type == BodyType::Dynamic {
+                let _ = world.insert_one(entity, PhysicsDriven);
+            }
+        }
+
+        for (&entity, synced) in &mut self.synced {
+            let Ok(transform) = world.get::<Transform>(entity) else {
+                continue;
+            };
+            let pose = (transform.position, transform.rotation);
+            let Some(rb) = self.rigid_body_set.get_mut(synced.handle.0) else {
+                continue;
+            };
+            match synced.body_type {
+                BodyType::Kinematic => rb.set_next_kinematic_position(isometry(pose.0, pose.1)),
+                BodyType::Dynamic | BodyType::Fixed if pose != synced.pose => {
+                    rb.set_position(isometry(pose.0, pose.1), true);
+                }
+                _ => {}
+            }
+            synced.pose = pose;
+        }
+    }
+
+    /// Write dynamic body poses and velocities back to the world
+    pub fn sync_from_physics(&mut self, world: &mut World) {
+        for (&entity, synced) in &mut self.synced {
+            if synced.body_type != BodyType::Dynamic {
+                continue;
+            }
+            let Some(rb) = self.rigid_body_set.get(synced.handle.0) else {
+                continue;
+            };
+            let translation = rb.translation();
+            let position = Vec3::new(translation.x, translation.y, translation.z);
+            let rotation = rapier_to_quat(rb.rotation());
+
+            if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+                transform.position = position;
+                transform.rotation = rotation;
+                synced.pose = (position, rotation);
+            }
+            if let Ok(mut velocity) = world.get_mut::<Velocity>(entity) {
+                let (linear, angular) = (rb.linvel(), rb.angvel());
+                velocity.linear = Vec3::new(linear.x, linear.y, linear.z);
+                velocity.angular = Vec3::new(angular.x, angular.y, angular.z);
+            }
+        }
+    }
+
+    /// Get the body created for an entity
+    #[must_use]
+    pub fn entity_body(&self, entity: Entity) -> Option<RigidBodyHandle> {
+        self.synced.get(&entity).map(|synced| synced.handle)
+    }
+
+    /// Get the entity a body was created for
+    #[must_use]
+    pub fn body_entity(&self, body: RigidBodyHandle) -> Option<Entity> {
+        self.rigid_body_set
+            .get(body.0)
+            .and_then(|rb| user_data_to_entity(rb.user_data))
+    }
+
+    /// Get the entity a collider was created for
+    #[must_use]
+    pub fn collider_entity(&self, collider: ColliderHandle) -> Option<Entity> {
+        self.collider_set
+            .get(collider.0)
+            .and_then(|c| user_data_to_entity(c.user_data))
+    }
+
+    /// Remove the body of an entity and the components added with it
+    fn remove_entity(&mut self, world: &mut World, entity: Entity) {
+        let Some(synced) = self.synced.remove(&entity) else {
+            return;
+        };
+        self.remove_body(synced.handle);
+        if world.contains(entity) {
+            let _ = world.remove_one::<RigidBodyHandle>(entity);
+            if synced.body_type == BodyType::Dynamic {
+                let _ = world.remove_one::<PhysicsDriven>(entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ground(world: &mut World) -> Entity {
+        world.spawn((
+            Transform::default(),
+            RigidBody::fixed(),
+            Collider::cuboid(Vec3::new(10.0, 0.5, 10.0)),
+        ))
+    }
+
+    #[test]
+    fn test_dynamic_body_falls_and_lands() {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        ground(&mut world);
+        let ball = world.spawn((
+            Transform::from_position(Vec3::new(0.0, 3.0, 0.0)),
+            Velocity::default(),
+            RigidBody::dynamic(),
+            Collider::ball(0.5),
+        ));
+
+        for _ in 0..120 {
+            physics.step_world(&mut world, 1.0 / 60.0);
+        }
+
+        let y = world.get::<Transform>(ball).unwrap().position.y;
+        assert!((y - 1.0).abs() < 0.05, "ball rests on the ground, y = {y}");
+        assert!(world.get::<PhysicsDriven>(ball).is_ok());
+        let handle = *world.get::<RigidBodyHandle>(ball).unwrap();
+        assert_eq!(physics.body_entity(handle), Some(ball));
+
+        world.despawn(ball).unwrap();
+        physics.sync_to_physics(&mut world);
+        assert_eq!(physics.entity_body(ball), None);
+        assert_eq!(physics.body_count(), 1);
+    }
+
+    #[test]
+    fn test_initial_velocity_is_kept() {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        let ball = world.spawn((
+            Transform::from_position(Vec3::new(0.0, 3.0, 0.0)),
+            Velocity {
+                linear: Vec3::new(5.0, 0.0, 0.0),
+                angular: Vec3::ZERO,
+            },
+            RigidBody::dynamic(),
+            Collider::ball(0.5),
+        ));
+
+        // Same order as the engine's fixed step
+        let dt = 1.0 / 60.0;
+        crate::ecs::integrate_velocities(&mut world, dt);
+        physics.step_world(&mut world, dt);
+
+        let velocity = world.get::<Velocity>(ball).unwrap().linear;
+        assert!((velocity.x - 5.0).abs() < 0.01, "vx = {}", velocity.x);
+        let x = world.get::<Transform>(ball).unwrap().position.x;
+        assert!((x - 5.0 * dt).abs() < 0.01, "moved once, x = {x}");
+    }
+
+    #[test]
+    fn test_kinematic_follows_transform() {
+        let mut physics = Physics::new();
+        let mut world = World::new();
+        let platform = world.spawn((
+            Transform::default(),
+            RigidBody::kinematic(),
+            Collider::cuboid(Vec3::ONE),
+        ));
+        physics.step_world(&mut world, 1.0 / 60.0);
+
+        world.get_mut::<Transform>(platform).unwrap().position = Vec3::new(2.0, 0.0, 0.0);
+        physics.step_world(&mut world, 1.0 / 60.0);
+
+        let body = physics.entity_body(platform).unwrap();
+        assert_eq!(physics.get_position(body), Some(Vec3::new(2.0, 0.0, 0.0)));
+        assert!(world.get::<PhysicsDriven>(platform).is_err());
+
+        // Disabling removes the body until the entity is enabled again
+        world.insert_one(platform, Disabled).unwrap();
+        physics.sync_to_physics(&mut world);
+        assert_eq!(physics.body_count(), 0);
+        assert!(world.get::<RigidBodyHandle>(platform).is_err());
+    }
+}
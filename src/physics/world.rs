@@ -3,8 +3,19 @@
 use glam::{Quat, Vec3};
 use nalgebra::UnitQuaternion;
 use rapier3d::prelude::*;
+use rustc_hash::FxHashMap;
+
+use super::components::active_events;
+use super::config::CollisionLayers;
+use super::events::{CollisionEvent, ContactForceEvent, EventCollector};
+use super::joints::{JointBroken, JointType};
+use super::sensors::{SensorEvent, SensorOverlaps};
+use super::sync::SyncedBody;
 
 /// Handle to a rigid body in the physics world
+///
+/// Also added as a component to entities whose body was created by
+/// [`Physics::step_world`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RigidBodyHandle(pub rapier3d::dynamics::RigidBodyHandle);
 
@@ -13,16 +24,24 @@ pub struct RigidBodyHandle(pub rapier3d::dynamics::RigidBodyHandle);
 pub struct ColliderHandle(pub rapier3d::geometry::ColliderHandle);
 
 /// Convert glam Quat to rapier3d UnitQuaternion
-fn quat_to_rapier(q: Quat) -> UnitQuaternion<f32> {
+pub(super) fn quat_to_rapier(q: Quat) -> UnitQuaternion<f32> {
     UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(q.w, q.x, q.y, q.z))
 }
 
 /// Convert rapier3d UnitQuaternion to glam Quat
-fn rapier_to_quat(uq: &UnitQuaternion<f32>) -> Quat {
+pub(super) fn rapier_to_quat(uq: &UnitQuaternion<f32>) -> Quat {
     let q = uq.quaternion();
     Quat::from_xyzw(q.i, q.j, q.k, q.w)
 }
 
+/// Build a rapier isometry from a position and rotation
+pub(super) fn isometry(position: Vec3, rotation: Quat) -> Isometry<f32> {
+    Isometry::from_parts(
+        nalgebra::Translation3::new(position.x, position.y, position.z),
+        quat_to_rapier(rotation),
+    )
+}
+
 /// Physics world manager
 pub struct Physics {
     /// Gravity vector
@@ -30,25 +49,41 @@ pub struct Physics {
     /// Physics pipeline
     pipeline: PhysicsPipeline,
     /// Island manager
-    island_manager: IslandManager,
+    pub(super) island_manager: IslandManager,
     /// Broad phase
     broad_phase: DefaultBroadPhase,
     /// Narrow phase
-    narrow_phase: NarrowPhase,
+    pub(super) narrow_phase: NarrowPhase,
     /// Rigid body set
-    rigid_body_set: RigidBodySet,
+    pub(super) rigid_body_set: RigidBodySet,
     /// Collider set
-    collider_set: ColliderSet,
+    pub(super) collider_set: ColliderSet,
     /// Impulse joint set
-    impulse_joint_set: ImpulseJointSet,
+    pub(super) impulse_joint_set: ImpulseJointSet,
     /// Multibody joint set
     multibody_joint_set: MultibodyJointSet,
     /// CCD solver
     ccd_solver: CCDSolver,
     /// Query pipeline for raycasting
-    query_pipeline: QueryPipeline,
+    pub(super) query_pipeline: QueryPipeline,
     /// Integration parameters
-    integration_parameters: IntegrationParameters,
+    pub(super) integration_parameters: IntegrationParameters,
+    /// Types of joints created through `create_joint`
+    pub(super) joint_types: FxHashMap<ImpulseJointHandle, JointType>,
+    /// Breaking forces of breakable joints
+    pub(super) breaking_forces: FxHashMap<ImpulseJointHandle, f32>,
+    /// Joints broken and not yet drained
+    pub(super) broken_joints: Vec<JointBroken>,
+    /// Named collision layers
+    pub(super) collision_layers: CollisionLayers,
+    /// Colliders overlapping each sensor as of the last step
+    pub(super) sensor_overlaps: SensorOverlaps,
+    /// Sensor events not yet drained
+    pub(super) sensor_events: Vec<SensorEvent>,
+    /// Collision and contact force events not yet drained
+    events: EventCollector,
+    /// Bodies created for ECS entities
+    pub(super) synced: FxHashMap<hecs::Entity, SyncedBody>,
 }
 
 impl Physics {
@@ -72,6 +107,14 @@ impl Physics {
             ccd_solver: CCDSolver::new(),
             query_pipeline: QueryPipeline::new(),
             integration_parameters: IntegrationParameters::default(),
+            joint_types: FxHashMap::default(),
+            breaking_forces: FxHashMap::default(),
+            broken_joints: Vec::new(),
+            collision_layers: CollisionLayers::new(),
+            sensor_overlaps: SensorOverlaps::default(),
+            sensor_events: Vec::new(),
+            events: EventCollector::default(),
+            synced: FxHashMap::default(),
         }
     }
 
@@ -92,8 +135,39 @@ impl Physics {
             &mut self.ccd_solver,
             Some(&mut self.query_pipeline),
             &(),
-            &(),
+            &self.events,
         );
+        self.break_joints();
+        self.update_sensors();
+    }
+
+    /// Take the collision events collected since the last call
+    pub fn drain_collision_events(&mut self) -> Vec<CollisionEvent> {
+        self.events.drain_collisions()
+    }
+
+    /// Take the contact force events collected since the last call
+    pub fn drain_contact_force_events(&mut self) -> Vec<ContactForceEvent> {
+        self.events.drain_contact_forces()
+    }
+
+    /// Enable or disable collision start and stop events for a collider
+    pub fn set_collision_events(&mut self, collider: ColliderHandle, enabled: bool) {
+        if let Some(c) = self.collider_set.get_mut(collider.0) {
+            let forces = c
+                .active_events()
+                .contains(ActiveEvents::CONTACT_FORCE_EVENTS);
+            c.set_active_events(active_events(enabled, forces));
+        }
+    }
+
+    /// Enable contact force events above a threshold for a collider, or disable them with `None`
+    pub fn set_contact_force_events(&mut self, collider: ColliderHandle, threshold: Option<f32>) {
+        if let Some(c) = self.collider_set.get_mut(collider.0) {
+            let collisions = c.active_events().contains(ActiveEvents::COLLISION_EVENTS);
+            c.set_active_events(active_events(collisions, threshold.is_some()));
+            c.set_contact_force_event_threshold(threshold.unwrap_or(0.0));
+        }
     }
 
     /// Create a static rigid body (doesn't move)
@@ -246,34 +320,22 @@ impl Physics {
         })
     }
 
-    /// Cast a ray and return the first hit
-    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RaycastHit> {
-        let ray = Ray::new(
-            point![origin.x, origin.y, origin.z],
-            vector![direction.x, direction.y, direction.z],
-        );
-
-        self.query_pipeline
-            .cast_ray(
-                &self.rigid_body_set,
-                &self.collider_set,
-                &ray,
-                max_distance,
-                true,
-                QueryFilter::default(),
-            )
-            .map(|(handle, distance)| {
-                let point = ray.point_at(distance);
-                RaycastHit {
-                    collider: ColliderHandle(handle),
-                    point: Vec3::new(point.x, point.y, point.z),
-                    distance,
-                }
-            })
+    /// Get the number of rigid bodies
+    pub fn body_count(&self) -> usize {
+        self.rigid_body_set.len()
     }
 
-    /// Remove a rigid body and its colliders
+    /// Remove a rigid body and its colliders and joints
     pub fn remove_body(&mut self, body: RigidBodyHandle) {
+        let joints: Vec<_> = self
+            .impulse_joint_set
+            .attached_joints(body.0)
+            .map(|(_, _, joint, _)| joint)
+            .collect();
+        for joint in joints {
+            self.joint_types.remove(&joint);
+            self.breaking_forces.remove(&joint);
+        }
         self.rigid_body_set.remove(
             body.0,
             &mut self.island_manager,
@@ -290,14 +352,3 @@ impl Default for Physics {
         Self::new()
     }
 }
-
-/// Result of a raycast
-#[derive(Debug, Clone)]
-pub struct RaycastHit {
-    /// The collider that was hit
-    pub collider: ColliderHandle,
-    /// The point of intersection
-    pub point: Vec3,
-    /// Distance from ray origin
-    pub distance: f32,
-}